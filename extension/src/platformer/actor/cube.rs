@@ -1,9 +1,11 @@
-use std::{cell::Cell, rc::Rc};
+use std::{cell::Cell, f32::consts::PI, rc::Rc};
 
 use godot::prelude::*;
-use godot::classes::Input;
+use godot::classes::{Input, Sprite2D, Texture2D};
 
-use super::{player::Player, Actor, ActorData, Directions, Reflection, SurfaceProperties};
+use super::{player::Player, Actor, ActorData, Directions, SurfaceProperties};
+use super::super::hold::HoldSlot;
+use super::super::lazer::{Mirror, MirrorSprite};
 
 const GRAB_DISTANCE: i32 = 4096;
 
@@ -25,6 +27,9 @@ pub struct Crate {
 	actor: Rc<Cell<ActorData>>,
 	push: Directions,
 	hold: HoldStatus,
+	pub hold_slot: Rc<Cell<HoldSlot>>,
+	overhead: bool,
+	flight: Option<i32>,
 
 	#[export]
 	push_speed: i32,
@@ -34,6 +39,11 @@ pub struct Crate {
 	can_flip_reflection: bool,
 	#[export]
 	sprite: Option<Gd<Node2D>>,
+	// Flat, shallow and diagonal mirror textures, swapped in as the mirror turns.
+	#[export]
+	mirror_textures: Array<Gd<Texture2D>>,
+	#[export]
+	throw_strength: i32,
 }
 
 #[godot_api]
@@ -44,18 +54,30 @@ impl INode for Crate {
 			actor: Default::default(),
 			push: Directions::empty(),
 			hold: HoldStatus::None,
+			hold_slot: Default::default(),
+			overhead: false,
+			flight: None,
 			push_speed: 0,
 			carryable: false,
 			can_flip_reflection: false,
 			sprite: None,
+			mirror_textures: Default::default(),
+			throw_strength: 30000,
 		}
 	}
 
 	fn ready(&mut self) {
-		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		let parent = self.base().get_parent().unwrap().try_cast::<Actor>().unwrap();
+		self.actor = Rc::clone(&parent.bind().data);
 		let mut data = self.actor.get();
 		data.notify_target = Some(self.base().instance_id());
+		data.pushable = true;
 		self.actor.set(data);
+
+		// The actor's ready, which sets up its mirror, hasn't run yet.
+		if let Some(mirror) = parent.bind().mirror().filter(|_| self.sprite.is_some()) {
+			self.show_mirror(mirror);
+		}
 	}
 
 	fn physics_process(&mut self, _: f64) {
@@ -64,42 +86,67 @@ impl INode for Crate {
 		match &mut self.hold {
 			HoldStatus::Holding(carrier_cell, side) => {
 				let carrier = carrier_cell.get();
-				data.pos = carrier.pos + carrier.area_offset + super::Vec { x: match *side {
-					HoldSide::Left => data.area_size.x / -2,
-					HoldSide::Right => carrier.area_size.x / 2,
-				} - data.area_offset.x, y: -data.area_offset.y };
-				data.vel = super::Vec::default();
-				if Input::singleton().is_action_just_pressed("hold") {
-					data.pos.x += (carrier.area_size.x / 2 - data.area_size.x) * match *side {
+				data.flipped = *side == HoldSide::Left;
+				// Letting go is refused while the spot beside the carrier is blocked,
+				// rather than dropping the crate into the carrier.
+				if Input::singleton().is_action_just_pressed("hold") && self.hold_slot.get().can_drop {
+					let facing = match *side {
 						HoldSide::Left => -1,
 						HoldSide::Right => 1,
 					};
+					// The first tick moves the crate clear of the carrier, swept like any
+					// other movement so it stops at walls; the throw takes over after.
+					let rect = data.rect();
+					let carrier_rect = carrier.rect();
 					data.vel = super::Vec {
 						x: match *side {
-							HoldSide::Left => carrier.vel.x.min(0) - data.area_size.x,
-							HoldSide::Right => data.area_size.x + carrier.vel.x.max(0),
+							HoldSide::Left => carrier_rect.tl.x + carrier.vel.x.min(0) - rect.br.x,
+							HoldSide::Right => carrier_rect.br.x + carrier.vel.x.max(0) - rect.tl.x,
 						},
 						y: carrier.vel.y,
 					};
+					// Up throws high, down sets the crate down in front (from overhead,
+					// onto whatever stack or ledge is there), and neither throws forward.
+					let input = Input::singleton();
+					if input.is_action_pressed("ui_up") {
+						data.vel.y -= self.throw_strength;
+						self.flight = Some(facing * self.throw_strength / 4);
+					} else if !input.is_action_pressed("ui_down") {
+						data.vel.y -= self.throw_strength / 3;
+						self.flight = Some(facing * self.throw_strength);
+					}
 					self.hold = HoldStatus::CanHold(carrier_cell.clone(), *side);
+					self.overhead = false;
+					data.held_by = None;
 					data.top |= SurfaceProperties::SOLID;
 					data.bottom |= SurfaceProperties::SOLID;
 					data.left |= SurfaceProperties::SOLID | SurfaceProperties::NOTIFY;
 					data.right |= SurfaceProperties::SOLID | SurfaceProperties::NOTIFY;
-				} else if carrier.vel.x != 0 {
-					*side = if carrier.vel.x < 0 { HoldSide::Left } else { HoldSide::Right };
+				} else {
+					if carrier.vel.x != 0 {
+						*side = if carrier.vel.x < 0 { HoldSide::Left } else { HoldSide::Right };
+					}
+					if Input::singleton().is_action_just_pressed("lift") {
+						self.overhead = !self.overhead;
+					}
+					data.hold_offset = hold_offset(&data, &carrier, *side, self.overhead);
 				}
-				if self.can_flip_reflection && Input::singleton().is_action_just_pressed("flip") {
-					let mut scale = self.sprite.as_ref().unwrap().get_scale();
-					scale.y *= -1.0;
-					self.sprite.as_mut().unwrap().set_scale(scale);
-					data.reflection = match data.reflection {
-						Reflection::None => Reflection::None,
-						Reflection::Main => Reflection::Inverse,
-						Reflection::Inverse => Reflection::Main,
+				if let Some(mirror) = data.mirror.filter(|_| self.can_flip_reflection) {
+					let input = Input::singleton();
+					let turned = if input.is_action_just_pressed("flip") {
+						mirror.flip()
+					} else if input.is_action_just_pressed("rotate") {
+						mirror.next()
+					} else {
+						mirror
 					};
+					if turned != mirror {
+						data.mirror = Some(turned);
+						self.show_mirror(turned);
+					}
 				}
 				self.actor.set(data);
+				self.update_slot();
 				return;
 			}
 			HoldStatus::CanHold(by, side) => {
@@ -108,10 +155,17 @@ impl INode for Crate {
 				let my_br = my_tl + data.area_size;
 				let by_tl = bydata.pos + bydata.area_offset;
 				let by_br = by_tl + bydata.area_size;
+				// A fresh press rather than a held button, since a crate that's just
+				// been let go lands within reach and would be grabbed straight back.
+				let grab = self.hold_slot.get().chosen && Input::singleton().is_action_just_pressed("hold");
 				if my_tl.x > by_br.x + GRAB_DISTANCE || my_br.x < by_tl.x - GRAB_DISTANCE || my_br.y > by_br.y + GRAB_DISTANCE || my_tl.y < by_br.y - (1 << 17) {
 					self.hold = HoldStatus::None;
-				} else if Input::singleton().is_action_pressed("hold") {
+				} else if grab {
+					data.hold_offset = hold_offset(&data, &bydata, *side, false);
+					self.overhead = false;
 					self.hold = HoldStatus::Holding(by.clone(), *side);
+					self.flight = None;
+					data.held_by = bydata.actor;
 					data.top = SurfaceProperties::OPAQUE;
 					data.bottom = SurfaceProperties::OPAQUE;
 					data.left = SurfaceProperties::OPAQUE;
@@ -122,13 +176,66 @@ impl INode for Crate {
 			HoldStatus::None => {}
 		}
 
-		data.vel.x = match self.push {
-			Directions::LEFT => self.push_speed,
-			Directions::RIGHT => -self.push_speed,
+		if self.flight.is_some() && data.collided.intersects(Directions::DOWN | Directions::LEFT | Directions::RIGHT) {
+			self.flight = None;
+		}
+
+		data.vel.x = match (self.flight, self.push) {
+			(Some(speed), _) => speed,
+			(None, Directions::LEFT) => self.push_speed,
+			(None, Directions::RIGHT) => -self.push_speed,
 			_ => 0
 		};
 		self.actor.set(data);
 		self.push = Directions::empty();
+		self.update_slot();
+	}
+}
+
+// Where a held crate sits relative to its carrier's position. Overhead, the
+// crate rests centred on the carrier's head, so the pair sweeps as one tall box.
+fn hold_offset(data: &ActorData, carrier: &ActorData, side: HoldSide, overhead: bool) -> super::Vec {
+	if overhead {
+		return carrier.area_offset + super::Vec {
+			x: (carrier.area_size.x - data.area_size.x) / 2 - data.area_offset.x,
+			y: -data.area_size.y - data.area_offset.y,
+		};
+	}
+	carrier.area_offset + super::Vec { x: match side {
+		HoldSide::Left => data.area_size.x / -2,
+		HoldSide::Right => carrier.area_size.x / 2,
+	} - data.area_offset.x, y: -data.area_offset.y }
+}
+
+impl Crate {
+	// Tells the game's hold manager whether this crate could be grabbed, and by whom.
+	fn update_slot(&self) {
+		let mut slot = self.hold_slot.get();
+		(slot.offered_to, slot.held) = match &self.hold {
+			HoldStatus::CanHold(by, _) if self.carryable => (by.get().actor, false),
+			HoldStatus::Holding(by, _) => (by.get().actor, true),
+			_ => (None, false),
+		};
+		self.hold_slot.set(slot);
+	}
+
+	fn show_mirror(&mut self, mirror: Mirror) {
+		let orientation = mirror.orientation();
+		let texture = self.mirror_textures.get(match orientation.sprite {
+			MirrorSprite::Flat => 0,
+			MirrorSprite::Shallow => 1,
+			MirrorSprite::Diagonal => 2,
+		});
+		let sprite = self.sprite.as_mut().unwrap();
+		let mut scale = sprite.get_scale().abs();
+		if orientation.flip {
+			scale.y *= -1.0;
+		}
+		sprite.set_scale(scale);
+		sprite.set_rotation(orientation.quarter_turns as f32 * PI * 0.5);
+		if let (Some(texture), Ok(mut sprite)) = (texture, sprite.clone().try_cast::<Sprite2D>()) {
+			sprite.set_texture(&texture);
+		}
 	}
 }
 
@@ -139,6 +246,7 @@ impl Crate {
 		if actor.get_child(0).and_then(|c| c.try_cast::<Player>().ok()).is_some() && actor.bind().data.get().collided_old.contains(Directions::DOWN) {
 			self.push |= Directions::from_bits_truncate(direction);
 			self.hold = HoldStatus::CanHold(Rc::clone(&actor.bind().data), if Directions::from_bits_truncate(direction) == Directions::LEFT { HoldSide::Right } else { HoldSide::Left });
+			self.update_slot();
 		}
 	}
 }
\ No newline at end of file
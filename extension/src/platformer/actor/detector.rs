@@ -2,9 +2,16 @@ use std::{cell::Cell, rc::Rc};
 
 use godot::{classes::{AnimatedSprite2D, Sprite2D}, prelude::*};
 
-use super::{Actor, ActorData, SurfaceProperties};
+use super::{Actor, ActorData, Directions, SurfaceProperties};
 
-const CHARGE_MAX: u32 = 32;
+#[derive(GodotConvert, Var, Export, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum DetectorFace {
+	#[default]
+	Accept,
+	Block,
+	Reflect,
+}
 
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -15,6 +22,20 @@ pub struct LazerDetector {
 
 	#[export]
 	fx: Option<Gd<Sprite2D>>,
+	#[export]
+	charge_max: u32,
+	#[export]
+	charge_rate: u32,
+	#[export]
+	discharge_rate: u32,
+	#[export]
+	top_face: DetectorFace,
+	#[export]
+	left_face: DetectorFace,
+	#[export]
+	bottom_face: DetectorFace,
+	#[export]
+	right_face: DetectorFace,
 }
 
 #[godot_api]
@@ -25,6 +46,13 @@ impl INode for LazerDetector {
 			actor: Default::default(),
 			charge: 0,
 			fx: None,
+			charge_max: 32,
+			charge_rate: 1,
+			discharge_rate: 1,
+			top_face: DetectorFace::Accept,
+			left_face: DetectorFace::Accept,
+			bottom_face: DetectorFace::Accept,
+			right_face: DetectorFace::Accept,
 		}
 	}
 
@@ -35,22 +63,39 @@ impl INode for LazerDetector {
 		data.left |= SurfaceProperties::OPAQUE;
 		data.bottom |= SurfaceProperties::OPAQUE;
 		data.right |= SurfaceProperties::OPAQUE;
+		for (face, direction) in [
+			(self.top_face, Directions::UP),
+			(self.left_face, Directions::LEFT),
+			(self.bottom_face, Directions::DOWN),
+			(self.right_face, Directions::RIGHT),
+		] {
+			match face {
+				DetectorFace::Accept => {}
+				DetectorFace::Block => data.beam_blind |= direction,
+				DetectorFace::Reflect => {
+					data.beam_blind |= direction;
+					data.beam_reflect |= direction;
+				}
+			}
+		}
 		self.actor.set(data);
 	}
 
 	fn physics_process(&mut self, _: f64) {
 		let mut data = self.actor.get();
-		if data.beams > 0 && self.charge < CHARGE_MAX {
-			self.charge += 1;
-		} else if data.beams == 0 && self.charge > 0 {
-			self.charge -= 1;
+		let charge_max = self.charge_max.max(1);
+		if data.beams > 0 {
+			self.charge = (self.charge + self.charge_rate).min(charge_max);
+		} else {
+			self.charge = self.charge.saturating_sub(self.discharge_rate);
 		}
 		if self.charge == 0 {
 			data.signal = false;
-		} else if self.charge == CHARGE_MAX {
+		} else if self.charge == charge_max {
 			data.signal = true;
 		}
+		data.level = self.charge as f32 / charge_max as f32;
 		self.actor.set(data);
-		self.fx.as_mut().unwrap().set_modulate(Color { r: 1.0, g: 1.0, b: 1.0, a: self.charge as f32 / CHARGE_MAX as f32 });
+		self.fx.as_mut().unwrap().set_modulate(Color { r: 1.0, g: 1.0, b: 1.0, a: data.level });
 	}
 }
\ No newline at end of file
@@ -2,9 +2,23 @@ use std::{cell::Cell, rc::Rc};
 
 use godot::{classes::{AnimatedSprite2D}, prelude::*};
 
-use super::{Actor, ActorData, SurfaceProperties};
+use super::{key::Key, Actor, ActorData, BlockMode, SurfaceProperties};
+use super::super::signal::Inputs;
 
 const TICKS_PER_FRAME: u32 = 2;
+const OPEN_FRAME: i32 = 8;
+
+#[derive(GodotConvert, Var, Export, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum SlideDirection {
+	// Up for tall doors, right for wide ones, matching how the gate sprite retracts.
+	#[default]
+	Auto,
+	Up,
+	Down,
+	Left,
+	Right,
+}
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
@@ -13,10 +27,31 @@ pub struct Door {
 	actor: Rc<Cell<ActorData>>,
 	sprite: Option<Gd<AnimatedSprite2D>>,
 	ttnf: u32,
-	input_actors: Box<[Rc<Cell<ActorData>>]>,
+	input_signals: Inputs,
+	closed_pos: super::Vec,
+	slide: super::Vec,
+	// The sprite isn't under the Actor, so a sliding door carries it along.
+	sprite_offset: Vector2,
 
 	#[export]
-	inputs: Array<Gd<Actor>>,
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
+	#[export]
+	analog: bool,
+	#[export]
+	blocks: BlockMode,
+	#[export]
+	sliding: bool,
+	#[export]
+	slide_direction: SlideDirection,
+	#[export]
+	slide_speed: i32,
+	#[export]
+	stop_on_obstruction: bool,
+	// Stays shut, whatever its inputs say, until a key with this id touches it.
+	#[export]
+	lock: GString,
 }
 
 #[godot_api]
@@ -27,24 +62,92 @@ impl INode2D for Door {
 			actor: Default::default(),
 			sprite: None,
 			ttnf: 0,
-			input_actors: Box::from([]),
+			input_signals: Default::default(),
+			closed_pos: Default::default(),
+			slide: Default::default(),
+			sprite_offset: Vector2::ZERO,
 			inputs: Default::default(),
+			inverted_inputs: Default::default(),
+			analog: false,
+			blocks: BlockMode::Actors,
+			sliding: false,
+			slide_direction: SlideDirection::Auto,
+			slide_speed: 8000,
+			stop_on_obstruction: false,
+			lock: GString::new(),
 		}
 	}
 
 	fn ready(&mut self) {
 		self.actor = Rc::clone(&self.base().get_child(0).unwrap().try_cast::<Actor>().unwrap().bind().data);
 		self.sprite = self.base().find_child("Sprite").map(|c| c.try_cast().unwrap());
-		self.input_actors = self.inputs.iter_shared().map(|input| Rc::clone(&input.bind().data)).collect();
+		self.input_signals = Inputs::new(&self.inputs, &self.inverted_inputs);
+
+		if self.sliding {
+			// Registered as a mover before PlatformerGame::ready collects actors.
+			let mut data = self.actor.get();
+			data.moves = true;
+			data.ignores_tiles = true;
+			data.blocked_by_actors = self.stop_on_obstruction;
+			data.top = self.blocks.properties();
+			data.bottom = self.blocks.properties();
+			data.left = self.blocks.properties();
+			data.right = self.blocks.properties();
+			self.closed_pos = data.pos;
+			let size = data.area_size;
+			self.slide = match self.slide_direction {
+				SlideDirection::Auto if size.x > size.y => super::Vec { x: size.x, y: 0 },
+				SlideDirection::Auto | SlideDirection::Up => super::Vec { x: 0, y: -size.y },
+				SlideDirection::Down => super::Vec { x: 0, y: size.y },
+				SlideDirection::Left => super::Vec { x: -size.x, y: 0 },
+				SlideDirection::Right => super::Vec { x: size.x, y: 0 },
+			};
+			self.actor.set(data);
+			if let Some(sprite) = &self.sprite {
+				self.sprite_offset = sprite.get_global_position() - Vector2::from(data.pos);
+			}
+		}
+	}
+
+	fn process(&mut self, _: f64) {
+		if !self.sliding {
+			return;
+		}
+		let pos = Vector2::from(self.actor.get().pos) + self.sprite_offset;
+		if let Some(sprite) = self.sprite.as_mut() {
+			sprite.set_global_position(pos);
+		}
 	}
 
 	fn physics_process(&mut self, _: f64) {
-		let open = self.input_actors.iter().all(|i| i.get().signal);
+		if !self.lock.is_empty() {
+			self.try_unlock();
+		}
+		let locked = !self.lock.is_empty();
+
+		let level = if locked { 0.0 } else { self.input_signals.level() };
+		let open = !locked && if self.analog {
+			level >= 1.0
+		} else {
+			self.input_signals.all()
+		};
+		let target = if self.analog {
+			(level * OPEN_FRAME as f32).round() as i32
+		} else if open {
+			OPEN_FRAME
+		} else {
+			0
+		};
+
+		if self.sliding {
+			self.slide(if self.analog { level } else if open { 1.0 } else { 0.0 });
+			return;
+		}
 
 		let mut data = self.actor.get();
 		let property = match open {
 			true => SurfaceProperties::empty(),
-			false => SurfaceProperties::SOLID
+			false => self.blocks.properties(),
 		};
 		let change = data.top != property;
 		data.top = property;
@@ -64,12 +167,55 @@ impl INode2D for Door {
 
 		let frame = self.sprite.as_ref().unwrap().get_frame();
 
-		if open && frame < 8 {
+		if frame < target {
 			self.sprite.as_mut().unwrap().set_frame(frame + 1);
 			self.ttnf = TICKS_PER_FRAME;
-		} else if !open && frame > 0 {
+		} else if frame > target {
 			self.sprite.as_mut().unwrap().set_frame(frame - 1);
 			self.ttnf = TICKS_PER_FRAME;
 		}
 	}
-}
\ No newline at end of file
+}
+
+impl Door {
+	fn try_unlock(&mut self) {
+		let Some(mut tree) = self.base().get_tree() else {
+			return;
+		};
+		let mut reach = self.actor.get().rect();
+		reach.tl = reach.tl + super::Vec { x: -1, y: -1 };
+		reach.br = reach.br + super::Vec { x: 1, y: 1 };
+
+		for key in tree.get_nodes_in_group("key").iter_shared() {
+			let Some(mut key) = key.find_child("Key").and_then(|key| key.try_cast::<Key>().ok()) else {
+				continue;
+			};
+			let matches = key.bind().id == self.lock;
+			let actor = Rc::clone(&key.bind().actor);
+			// A consumed key stays in the group until it's freed at the end of the frame.
+			if matches && !actor.get().removed && actor.get().rect().intersects(reach) {
+				// Marks it removed, so no other door can use it this tick.
+				key.bind_mut().consume();
+				self.lock = GString::new();
+				return;
+			}
+		}
+	}
+
+	// Moves the collision box towards `fraction` of the way open; the engine
+	// pushes actors out of the way or, if blocked or about to crush one against
+	// something solid, holds the door back.
+	fn slide(&mut self, fraction: f32) {
+		let mut data = self.actor.get();
+		let target = self.closed_pos + super::Vec {
+			x: (self.slide.x as f32 * fraction) as i32,
+			y: (self.slide.y as f32 * fraction) as i32,
+		};
+		let offset = target - data.pos;
+		data.vel = super::Vec {
+			x: offset.x.clamp(-self.slide_speed, self.slide_speed),
+			y: offset.y.clamp(-self.slide_speed, self.slide_speed),
+		};
+		self.actor.set(data);
+	}
+}
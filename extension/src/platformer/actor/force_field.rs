@@ -0,0 +1,63 @@
+use std::{cell::Cell, rc::Rc};
+
+use godot::prelude::*;
+
+use super::{Actor, ActorData, BlockMode, SurfaceProperties};
+use super::super::signal::Inputs;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct ForceField {
+	base: Base<Node>,
+	actor: Rc<Cell<ActorData>>,
+	input_signals: Inputs,
+
+	#[export]
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
+	#[export]
+	blocks: BlockMode,
+	#[export]
+	sprite: Option<Gd<Node2D>>,
+}
+
+#[godot_api]
+impl INode for ForceField {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			actor: Default::default(),
+			input_signals: Default::default(),
+			inputs: Default::default(),
+			inverted_inputs: Default::default(),
+			blocks: BlockMode::Beams,
+			sprite: None,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		self.input_signals = Inputs::new(&self.inputs, &self.inverted_inputs);
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		let on = self.input_signals.all();
+		let property = if on { self.blocks.properties() } else { SurfaceProperties::empty() };
+
+		// Synced every tick, so the sprite also matches a field that starts off.
+		if let Some(sprite) = self.sprite.as_mut() {
+			sprite.set_visible(on);
+		}
+
+		let mut data = self.actor.get();
+		if data.top == property {
+			return;
+		}
+		data.top = property;
+		data.bottom = property;
+		data.left = property;
+		data.right = property;
+		self.actor.set(data);
+	}
+}
@@ -0,0 +1,125 @@
+use std::{cell::Cell, collections::VecDeque, rc::Rc};
+
+use godot::prelude::*;
+
+use super::{Actor, ActorData};
+use super::super::signal::Inputs;
+
+#[derive(GodotConvert, Var, Export, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum GateKind {
+	#[default]
+	And,
+	Or,
+	Xor,
+	Not,
+	// Turns on at a rising edge of the first input, off at one of the second.
+	Latch,
+	// Toggles at each rising edge of any input.
+	FlipFlop,
+	// Outputs the AND of its inputs `ticks` ticks late.
+	Delay,
+	// Stays on for `ticks` ticks after each rising edge of the AND of its inputs.
+	Pulse,
+	// Turns on after `count` rising edges of the first input; the second resets it.
+	Counter,
+}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct Gate {
+	base: Base<Node>,
+	actor: Rc<Cell<ActorData>>,
+	input_signals: Inputs,
+	previous: Box<[bool]>,
+	history: VecDeque<bool>,
+	timer: u32,
+	counted: u32,
+
+	#[export]
+	kind: GateKind,
+	#[export]
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
+	#[export]
+	ticks: u32,
+	#[export]
+	count: u32,
+}
+
+#[godot_api]
+impl INode for Gate {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			actor: Default::default(),
+			input_signals: Default::default(),
+			previous: Box::from([]),
+			history: VecDeque::new(),
+			timer: 0,
+			counted: 0,
+			kind: GateKind::And,
+			inputs: Default::default(),
+			inverted_inputs: Default::default(),
+			ticks: 30,
+			count: 2,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		self.input_signals = Inputs::new(&self.inputs, &self.inverted_inputs);
+		self.previous = self.input_signals.signals().map(|_| false).collect();
+		self.history = std::iter::repeat_n(false, self.ticks as usize).collect();
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		let current: Box<[bool]> = self.input_signals.signals().collect();
+		let rising = |i: usize| current.get(i).is_some_and(|&on| on && !self.previous[i]);
+		let all = current.iter().all(|&on| on);
+
+		let mut data = self.actor.get();
+		let signal = match self.kind {
+			GateKind::And => all,
+			GateKind::Or => current.iter().any(|&on| on),
+			GateKind::Xor => current.iter().filter(|&&on| on).count() % 2 == 1,
+			GateKind::Not => !all,
+			GateKind::Latch => if rising(0) {
+				true
+			} else if rising(1) {
+				false
+			} else {
+				data.signal
+			},
+			GateKind::FlipFlop => data.signal ^ (0..current.len()).any(rising),
+			GateKind::Delay => {
+				self.history.push_back(all);
+				self.history.pop_front().unwrap()
+			}
+			GateKind::Pulse => {
+				if all && !self.previous.iter().all(|&on| on) {
+					self.timer = self.ticks;
+				}
+				if self.timer > 0 {
+					self.timer -= 1;
+					true
+				} else {
+					false
+				}
+			}
+			GateKind::Counter => {
+				if rising(1) {
+					self.counted = 0;
+				} else if rising(0) {
+					self.counted += 1;
+				}
+				self.counted >= self.count
+			}
+		};
+		data.signal = signal;
+		data.level = if signal { 1.0 } else { 0.0 };
+		self.actor.set(data);
+		self.previous = current;
+	}
+}
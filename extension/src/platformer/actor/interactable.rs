@@ -0,0 +1,150 @@
+use std::{cell::Cell, rc::Rc};
+
+use godot::{classes::{AnimatedSprite2D, Input}, prelude::*};
+
+use super::{Actor, ActorData};
+
+const REACH: i32 = 1 << 15;
+
+// Shared by anything the player switches with the "interact" action.
+struct Interactable {
+	actor: Rc<Cell<ActorData>>,
+	player: Option<Rc<Cell<ActorData>>>,
+}
+
+impl Interactable {
+	fn new(node: &Gd<Node>) -> Self {
+		Self {
+			actor: Rc::clone(&node.get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data),
+			player: None,
+		}
+	}
+
+	// The player can use it while overlapping it or standing just in front of it.
+	fn used(&mut self, node: &Gd<Node>) -> bool {
+		if !Input::singleton().is_action_just_pressed("interact") {
+			return false;
+		}
+
+		if self.player.is_none() {
+			self.player = node.get_tree()
+				.and_then(|mut tree| tree.get_first_node_in_group("player"))
+				.and_then(|player| player.try_cast::<Actor>().ok())
+				.map(|player| Rc::clone(&player.bind().data));
+		}
+		let Some(player) = &self.player else {
+			return false;
+		};
+
+		let player = player.get();
+		let mut reach = player.rect();
+		if player.flipped {
+			reach.tl.x -= REACH;
+		} else {
+			reach.br.x += REACH;
+		}
+		reach.intersects(self.actor.get().rect())
+	}
+
+	fn set(&self, on: bool) {
+		let mut data = self.actor.get();
+		data.signal = on;
+		data.level = if on { 1.0 } else { 0.0 };
+		self.actor.set(data);
+	}
+}
+
+fn show(sprite: &mut Option<Gd<AnimatedSprite2D>>, on: bool) {
+	if let Some(sprite) = sprite {
+		sprite.set_frame(if on { 1 } else { 0 });
+	}
+}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct Lever {
+	base: Base<Node>,
+	interactable: Option<Interactable>,
+
+	#[export]
+	on: bool,
+	#[export]
+	sprite: Option<Gd<AnimatedSprite2D>>,
+}
+
+#[godot_api]
+impl INode for Lever {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			interactable: None,
+			on: false,
+			sprite: None,
+		}
+	}
+
+	fn ready(&mut self) {
+		let interactable = Interactable::new(&self.base());
+		interactable.set(self.on);
+		self.interactable = Some(interactable);
+		show(&mut self.sprite, self.on);
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		let node = self.base().clone();
+		let interactable = self.interactable.as_mut().unwrap();
+		if interactable.used(&node) {
+			self.on = !self.on;
+			interactable.set(self.on);
+			show(&mut self.sprite, self.on);
+		}
+	}
+}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct TimedButton {
+	base: Base<Node>,
+	interactable: Option<Interactable>,
+	remaining: u32,
+
+	#[export]
+	ticks: u32,
+	#[export]
+	sprite: Option<Gd<AnimatedSprite2D>>,
+}
+
+#[godot_api]
+impl INode for TimedButton {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			interactable: None,
+			remaining: 0,
+			ticks: 120,
+			sprite: None,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.interactable = Some(Interactable::new(&self.base()));
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		let node = self.base().clone();
+		let interactable = self.interactable.as_mut().unwrap();
+		let was_on = self.remaining > 0;
+
+		if interactable.used(&node) {
+			self.remaining = self.ticks;
+		} else if self.remaining > 0 {
+			self.remaining -= 1;
+		}
+
+		let on = self.remaining > 0;
+		if on != was_on {
+			interactable.set(on);
+			show(&mut self.sprite, on);
+		}
+	}
+}
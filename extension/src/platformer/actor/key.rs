@@ -0,0 +1,47 @@
+use std::{cell::Cell, rc::Rc};
+
+use godot::prelude::*;
+
+use super::{Actor, ActorData, SurfaceProperties};
+
+// Sits next to a Crate so it's carried the same way; a Door whose `lock`
+// matches `id` uses it up on contact.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct Key {
+	base: Base<Node>,
+	pub actor: Rc<Cell<ActorData>>,
+
+	#[export]
+	pub id: GString,
+}
+
+#[godot_api]
+impl INode for Key {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			actor: Default::default(),
+			id: GString::new(),
+		}
+	}
+
+	fn ready(&mut self) {
+		let mut parent = self.base().get_parent().unwrap();
+		self.actor = Rc::clone(&parent.clone().try_cast::<Actor>().unwrap().bind().data);
+		parent.add_to_group("key");
+	}
+}
+
+impl Key {
+	pub fn consume(&mut self) {
+		let mut data = self.actor.get();
+		data.removed = true;
+		data.top = SurfaceProperties::empty();
+		data.bottom = SurfaceProperties::empty();
+		data.left = SurfaceProperties::empty();
+		data.right = SurfaceProperties::empty();
+		self.actor.set(data);
+		self.base().get_parent().unwrap().queue_free();
+	}
+}
@@ -1,7 +1,8 @@
-use std::{cell::Cell, ops::Add, rc::Rc};
+use std::{cell::Cell, ops::{Add, Sub}, rc::Rc};
 
 use godot::{classes::{CollisionShape2D, RectangleShape2D}, prelude::*};
 use bitflags::bitflags;
+use super::lazer::Mirror;
 
 mod player;
 mod cube;
@@ -9,6 +10,16 @@ mod plate;
 mod door;
 mod detector;
 mod unlock_book_zone;
+mod rotator;
+mod gate;
+mod sequence_lock;
+mod interactable;
+mod force_field;
+mod key;
+
+pub use cube::Crate;
+pub use gate::GateKind;
+pub use rotator::Rotator;
 
 const SCENE_SCALE: f32 = 65536.0;
 pub const SCENE_SCALE_INV: f32 = 1.0 / SCENE_SCALE;
@@ -16,7 +27,7 @@ pub const SCENE_SCALE_INV: f32 = 1.0 / SCENE_SCALE;
 const GRAVITY: i32 = 3000;
 
 bitflags! {
-	#[derive(Default, Clone, Copy, PartialEq, Eq)]
+	#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 	pub struct Directions: u8 {
 		const UP = 1;
 		const DOWN = 2;
@@ -45,7 +56,26 @@ impl SurfaceProperties {
 	}
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(GodotConvert, Var, Export, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum BlockMode {
+	#[default]
+	Actors,
+	Beams,
+	Both,
+}
+
+impl BlockMode {
+	pub const fn properties(self) -> SurfaceProperties {
+		match self {
+			Self::Actors => SurfaceProperties::SOLID,
+			Self::Beams => SurfaceProperties::OPAQUE,
+			Self::Both => SurfaceProperties::SOLID.union(SurfaceProperties::OPAQUE),
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Vec {
 	pub x: i32,
 	pub y: i32,
@@ -69,6 +99,17 @@ impl From<Vector2> for Vec {
 	}
 }
 
+impl Sub for Vec {
+	type Output = Vec;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self {
+			x: self.x - rhs.x,
+			y: self.y - rhs.y,
+		}
+	}
+}
+
 impl Add for Vec {
 	type Output = Vec;
 
@@ -87,6 +128,58 @@ impl Vec {
 			y: self.y >> 1,
 		}
 	}
+
+	pub const fn rotate_cw(self) -> Self {
+		Self {
+			x: -self.y,
+			y: self.x,
+		}
+	}
+
+	pub const fn rotate_ccw(self) -> Self {
+		Self {
+			x: self.y,
+			y: -self.x,
+		}
+	}
+
+	pub fn min(self, other: Self) -> Self {
+		Self {
+			x: self.x.min(other.x),
+			y: self.y.min(other.y),
+		}
+	}
+
+	pub fn max(self, other: Self) -> Self {
+		Self {
+			x: self.x.max(other.x),
+			y: self.y.max(other.y),
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+	pub tl: Vec,
+	pub br: Vec,
+}
+
+impl Rect {
+	pub fn union(self, other: Self) -> Self {
+		Self {
+			tl: self.tl.min(other.tl),
+			br: self.br.max(other.br),
+		}
+	}
+
+	pub fn intersects(self, other: Self) -> bool {
+		self.br.x >= other.tl.x && self.br.y >= other.tl.y && self.tl.x <= other.br.x && self.tl.y <= other.br.y
+	}
+
+	// Like `intersects`, but rects that only touch don't count.
+	pub fn overlaps(self, other: Self) -> bool {
+		self.br.x > other.tl.x && self.br.y > other.tl.y && self.tl.x < other.br.x && self.tl.y < other.br.y
+	}
 }
 
 pub struct Edge {
@@ -95,14 +188,6 @@ pub struct Edge {
 	pub properties: SurfaceProperties
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Default)]
-pub enum Reflection {
-	#[default]
-	None,
-	Main,
-	Inverse,
-}
-
 #[derive(Default, Clone, Copy)]
 pub struct ActorData {
 	pub moves: bool,
@@ -116,8 +201,23 @@ pub struct ActorData {
 	pub actor: Option<InstanceId>,
 	pub notify_target: Option<InstanceId>,
 	pub signal: bool,
+	pub level: f32,
+	pub mass: u32,
+	pub stack_mass: u32,
+	pub standing_on: Option<InstanceId>,
+	pub held_by: Option<InstanceId>,
+	pub hold_offset: Vec,
+	pub ignores_tiles: bool,
+	pub pushable: bool,
+	pub blocked_by_actors: bool,
+	pub removed: bool,
 	pub beams: u32,
-	pub reflection: Reflection,
+	pub beam_blind: Directions,
+	pub beam_reflect: Directions,
+	// Its notify target takes `beam_notify`, whether or not it blocks beams.
+	pub beam_effects: bool,
+	pub mirror: Option<Mirror>,
+	pub flipped: bool,
 	gravity: i32,
 	terminal_velocity: i32,
 	pub top: SurfaceProperties,
@@ -155,6 +255,12 @@ pub struct Actor {
 	reflective: bool,
 	#[export]
 	invert_reflection_direction: bool,
+	#[export]
+	mirror_angle: i32,
+	#[export]
+	opaque: bool,
+	#[export]
+	mass: u32,
 
 	base: Base<Node2D>,
 }
@@ -176,6 +282,9 @@ impl INode2D for Actor {
 			right_notify: false,
 			reflective: false,
 			invert_reflection_direction: false,
+			mirror_angle: 45,
+			opaque: false,
+			mass: 1,
 			base,
 		}
 	}
@@ -184,15 +293,14 @@ impl INode2D for Actor {
 		let mut data = self.data.get();
 
 		data.moves = !self.is_static;
+		data.mass = self.mass;
+		data.stack_mass = self.mass;
 		data.pos = self.base().get_global_position().into();
 		data.actor = Some(self.base().instance_id());
-		data.reflection = if !self.reflective {
-			Reflection::None
-		} else if self.invert_reflection_direction {
-			Reflection::Inverse
-		} else {
-			Reflection::Main
-		};
+		if self.reflective && Mirror::from_angle(self.mirror_angle).is_none() {
+			godot_error!("{} has a mirror angle of {} degrees; there is no sprite for it", self.base().get_name(), self.mirror_angle);
+		}
+		data.mirror = self.mirror();
 		if self.top_solid { data.top |= SurfaceProperties::SOLID | SurfaceProperties::OPAQUE; }
 		if self.top_notify { data.top |= SurfaceProperties::NOTIFY; }
 		if self.left_solid { data.left |= SurfaceProperties::SOLID | SurfaceProperties::OPAQUE; }
@@ -201,6 +309,12 @@ impl INode2D for Actor {
 		if self.bottom_notify { data.bottom |= SurfaceProperties::NOTIFY; }
 		if self.right_solid { data.right |= SurfaceProperties::SOLID | SurfaceProperties::OPAQUE; }
 		if self.right_notify { data.right |= SurfaceProperties::NOTIFY; }
+		if self.opaque {
+			data.top |= SurfaceProperties::OPAQUE;
+			data.left |= SurfaceProperties::OPAQUE;
+			data.bottom |= SurfaceProperties::OPAQUE;
+			data.right |= SurfaceProperties::OPAQUE;
+		}
 
 		if self.terminal_velocity > 0 {
 			data.gravity = GRAVITY;
@@ -229,7 +343,40 @@ impl INode2D for Actor {
 	}
 }
 
+impl Actor {
+	// The mirror set up in the editor. Children can ask for it in their own
+	// ready, before this actor's has run.
+	pub fn mirror(&self) -> Option<Mirror> {
+		self.reflective.then(|| {
+			let mirror = Mirror::from_angle(self.mirror_angle).unwrap_or_else(|| Mirror::from_angle(45).unwrap());
+			if self.invert_reflection_direction { mirror.flip() } else { mirror }
+		})
+	}
+}
+
 impl ActorData {
+	pub fn rect(&self) -> Rect {
+		let tl = self.pos + self.area_offset;
+		Rect {
+			tl,
+			br: tl + self.area_size,
+		}
+	}
+
+	pub fn support(&self) -> Option<InstanceId> {
+		self.held_by.or(self.standing_on)
+	}
+
+	pub fn beam_state_changed(&self, old: &ActorData) -> bool {
+		self.rect() != old.rect() ||
+			self.top != old.top ||
+			self.left != old.left ||
+			self.bottom != old.bottom ||
+			self.right != old.right ||
+			self.beam_reflect != old.beam_reflect ||
+			self.mirror != old.mirror
+	}
+
 	pub fn top_edge(&self) -> Edge {
 		Edge {
 			pos: self.pos + self.area_offset,
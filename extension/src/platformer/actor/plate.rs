@@ -2,7 +2,29 @@ use std::{cell::Cell, rc::Rc};
 
 use godot::{classes::AnimatedSprite2D, prelude::*};
 
-use super::{Actor, ActorData};
+use super::{Actor, ActorData, Directions, SurfaceProperties};
+
+// The side of the plate that faces out of the floor, wall or ceiling it's mounted on.
+#[derive(GodotConvert, Var, Export, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum PlateFace {
+	#[default]
+	Top,
+	Bottom,
+	Left,
+	Right,
+}
+
+impl PlateFace {
+	const fn back(self) -> Directions {
+		match self {
+			Self::Top => Directions::DOWN,
+			Self::Bottom => Directions::UP,
+			Self::Left => Directions::RIGHT,
+			Self::Right => Directions::LEFT,
+		}
+	}
+}
 
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -13,6 +35,10 @@ pub struct Plate {
 	
 	#[export]
 	sprite: Option<Gd<AnimatedSprite2D>>,
+	#[export]
+	min_weight: u32,
+	#[export]
+	face: PlateFace,
 }
 
 #[godot_api]
@@ -23,6 +49,8 @@ impl INode for Plate {
 			actor: Default::default(),
 			pushing: vec![],
 			sprite: None,
+			min_weight: 1,
+			face: PlateFace::Top,
 		}
 	}
 
@@ -30,28 +58,44 @@ impl INode for Plate {
 		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
 		let mut data = self.actor.get();
 		data.notify_target = Some(self.base().instance_id());
+		match self.face {
+			PlateFace::Top => data.top |= SurfaceProperties::NOTIFY,
+			PlateFace::Bottom => data.bottom |= SurfaceProperties::NOTIFY,
+			PlateFace::Left => data.left |= SurfaceProperties::NOTIFY,
+			PlateFace::Right => data.right |= SurfaceProperties::NOTIFY,
+		}
 		self.actor.set(data);
 	}
 
 	fn physics_process(&mut self, _: f64) {
 		let mut data = self.actor.get();
-		let plate_tl = data.pos + data.area_offset;
-		let plate_r = plate_tl.x + data.area_size.x;
+		let plate = data.rect();
+		let face = self.face;
 
 		self.pushing.retain(|weight| {
-			let data = weight.get();
-			let tl = data.pos + data.area_offset;
-			let br = tl + data.area_size;
-			br.x > plate_tl.x && tl.x < plate_r && br.y > plate_tl.y
+			let actor = weight.get().rect();
+			let across = actor.br.x > plate.tl.x && actor.tl.x < plate.br.x;
+			let along = actor.br.y > plate.tl.y && actor.tl.y < plate.br.y;
+			match face {
+				PlateFace::Top => across && actor.br.y > plate.tl.y,
+				PlateFace::Bottom => across && actor.tl.y < plate.br.y,
+				PlateFace::Left => along && actor.br.x > plate.tl.x,
+				PlateFace::Right => along && actor.tl.x < plate.br.x,
+			}
 		});
 
-		if data.signal && self.pushing.is_empty() {
+		let weight: u32 = self.pushing.iter().map(|actor| actor.get().stack_mass).sum();
+		let pressed = !self.pushing.is_empty() && weight >= self.min_weight;
+
+		if data.signal && !pressed {
 			self.sprite.as_mut().unwrap().set_animation("NotPressed");
 			data.signal = false;
+			data.level = 0.0;
 			self.actor.set(data);
-		} else if !data.signal && !self.pushing.is_empty() {
+		} else if !data.signal && pressed {
 			self.sprite.as_mut().unwrap().set_animation("Pressed");
 			data.signal = true;
+			data.level = 1.0;
 			self.actor.set(data);
 		}
 	}
@@ -60,7 +104,10 @@ impl INode for Plate {
 #[godot_api]
 impl Plate {
 	#[func]
-	fn collide_notify(&mut self, actor: Gd<Actor>, _: u8) {
+	fn collide_notify(&mut self, actor: Gd<Actor>, direction: u8) {
+		if Directions::from_bits_truncate(direction) == self.face.back() {
+			return;
+		}
 		let actor = Rc::clone(&actor.bind().data);
 		if !self.pushing.iter().any(|a| Rc::ptr_eq(a, &actor)) {
 			self.pushing.push(actor);
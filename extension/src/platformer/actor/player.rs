@@ -4,6 +4,7 @@ use godot::{classes::AnimatedSprite2D, prelude::*};
 use godot::classes::Input;
 
 use super::{Actor, ActorData, Directions, GRAVITY};
+use super::super::lazer::BeamEffect;
 
 pub static HAS_BOOK: AtomicBool = AtomicBool::new(false); 
 
@@ -14,6 +15,8 @@ pub struct Player {
 	actor: Rc<Cell<ActorData>>,
 	book: Rc<Cell<ActorData>>,
 	can_book: bool,
+	knockback: super::Vec,
+	dying: bool,
 
 	#[export]
 	speed: i32,
@@ -26,6 +29,8 @@ pub struct Player {
 	#[export]
 	book_bounce: i32,
 	#[export]
+	knockback_power: i32,
+	#[export]
 	sprite: Option<Gd<AnimatedSprite2D>>,
 }
 
@@ -37,17 +42,25 @@ impl INode for Player {
 			actor: Default::default(),
 			book: Default::default(),
 			can_book: true,
+			knockback: Default::default(),
+			dying: false,
 			speed: 16000,
 			jump_power: 50000,
 			jump_gravity: 1500,
 			jump_gravity_cutoff: 10000,
 			book_bounce: 0,
+			knockback_power: 30000,
 			sprite: None,
 		}
 	}
 
 	fn ready(&mut self) {
 		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		let mut data = self.actor.get();
+		data.notify_target = Some(self.base().instance_id());
+		data.beam_effects = true;
+		self.actor.set(data);
+		self.base().get_parent().unwrap().add_to_group("player");
 		self.book = Rc::clone(&self.base().get_parent().unwrap().get_parent().unwrap().find_child("Book").unwrap().try_cast::<Actor>().unwrap().bind().data);
 	}
 
@@ -55,9 +68,13 @@ impl INode for Player {
 		let input = Input::singleton();
 		let mut data = self.actor.get();
 
-		data.vel.x = 0;
-		if input.is_action_pressed("ui_left") { data.vel.x -= self.speed; self.sprite.as_mut().unwrap().set_scale(Vector2 { x: -0.0625, y: 0.0625 });}
-		if input.is_action_pressed("ui_right") { data.vel.x += self.speed; self.sprite.as_mut().unwrap().set_scale(Vector2 { x: 0.0625, y: 0.0625 }); }
+		data.vel.x = self.knockback.x;
+		if self.knockback.y != 0 {
+			data.vel.y = self.knockback.y;
+		}
+		self.knockback = super::Vec { x: self.knockback.x * 3 / 4, y: 0 };
+		if input.is_action_pressed("ui_left") { data.vel.x -= self.speed; data.flipped = true; self.sprite.as_mut().unwrap().set_scale(Vector2 { x: -0.0625, y: 0.0625 });}
+		if input.is_action_pressed("ui_right") { data.vel.x += self.speed; data.flipped = false; self.sprite.as_mut().unwrap().set_scale(Vector2 { x: 0.0625, y: 0.0625 }); }
 
 		if data.gravity == self.jump_gravity && (
 			data.vel.y > self.jump_gravity_cutoff ||
@@ -91,4 +108,27 @@ impl INode for Player {
 
 		self.actor.set(data);
 	}
+}
+
+#[godot_api]
+impl Player {
+	#[func]
+	fn beam_notify(&mut self, effect: BeamEffect, direction: u8) {
+		match effect {
+			BeamEffect::None => {}
+			// The beam keeps touching until the reload happens, so only queue it once.
+			BeamEffect::Kill if !self.dying => {
+				self.dying = true;
+				self.base().get_tree().unwrap().call_deferred("reload_current_scene", &[]);
+			}
+			BeamEffect::Kill => {}
+			BeamEffect::Knockback => {
+				let direction = Directions::from_bits_truncate(direction);
+				self.knockback = super::Vec {
+					x: if direction.contains(Directions::LEFT) { -self.knockback_power } else if direction.contains(Directions::RIGHT) { self.knockback_power } else { 0 },
+					y: if direction.contains(Directions::UP) { -self.knockback_power } else if direction.contains(Directions::DOWN) { self.knockback_power } else { 0 },
+				};
+			}
+		}
+	}
 }
\ No newline at end of file
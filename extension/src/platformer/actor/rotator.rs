@@ -0,0 +1,109 @@
+use std::{cell::Cell, f32::consts::PI, rc::Rc};
+
+use godot::prelude::*;
+
+use super::{Actor, ActorData};
+use super::super::signal::Inputs;
+
+pub struct Rotator {
+	inputs: Inputs,
+	was_on: bool,
+	ticks: u32,
+}
+
+impl Rotator {
+	pub fn new(inputs: Inputs) -> Self {
+		Self {
+			inputs,
+			was_on: false,
+			ticks: 0,
+		}
+	}
+
+	// Returns whether to make a quarter turn this tick: once when the inputs
+	// turn on, then every `period` ticks while they stay on if `continuous`.
+	pub fn tick(&mut self, continuous: bool, period: u32) -> bool {
+		if self.inputs.is_empty() {
+			return false;
+		}
+
+		let on = self.inputs.all();
+		let rising = on && !self.was_on;
+		self.was_on = on;
+
+		if !on {
+			self.ticks = 0;
+			false
+		} else if rising {
+			self.ticks = 0;
+			true
+		} else if continuous {
+			self.ticks += 1;
+			if self.ticks >= period.max(1) {
+				self.ticks = 0;
+				true
+			} else {
+				false
+			}
+		} else {
+			false
+		}
+	}
+}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct RotatingMirror {
+	base: Base<Node>,
+	actor: Rc<Cell<ActorData>>,
+	rotator: Option<Rotator>,
+
+	#[export]
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
+	#[export]
+	rotate_continuously: bool,
+	#[export]
+	rotate_period: u32,
+	#[export]
+	clockwise: bool,
+	#[export]
+	sprite: Option<Gd<Node2D>>,
+}
+
+#[godot_api]
+impl INode for RotatingMirror {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			actor: Default::default(),
+			rotator: None,
+			inputs: Default::default(),
+			inverted_inputs: Default::default(),
+			rotate_continuously: false,
+			rotate_period: 60,
+			clockwise: true,
+			sprite: None,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		self.rotator = Some(Rotator::new(Inputs::new(&self.inputs, &self.inverted_inputs)));
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		if !self.rotator.as_mut().unwrap().tick(self.rotate_continuously, self.rotate_period) {
+			return;
+		}
+
+		let mut data = self.actor.get();
+		data.mirror = data.mirror.map(|mirror| mirror.turn(self.clockwise));
+		self.actor.set(data);
+
+		if let Some(sprite) = self.sprite.as_mut() {
+			sprite.rotate(if self.clockwise { PI * 0.5 } else { PI * -0.5 });
+		}
+	}
+}
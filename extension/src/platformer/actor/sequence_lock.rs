@@ -0,0 +1,118 @@
+use std::{cell::Cell, rc::Rc};
+
+use godot::{classes::{AnimatedSprite2D, AudioStreamPlayer2D}, prelude::*};
+
+use super::{Actor, ActorData};
+use super::super::signal::Inputs;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct SequenceLock {
+	base: Base<Node>,
+	actor: Rc<Cell<ActorData>>,
+	input_signals: Inputs,
+	previous: Box<[bool]>,
+	progress: usize,
+
+	#[export]
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
+	// Indices into `inputs`; empty means in the order they're listed.
+	#[export]
+	sequence: PackedInt32Array,
+	#[export]
+	sprite: Option<Gd<AnimatedSprite2D>>,
+	#[export]
+	step_sound: Option<Gd<AudioStreamPlayer2D>>,
+	#[export]
+	fail_sound: Option<Gd<AudioStreamPlayer2D>>,
+}
+
+#[godot_api]
+impl INode for SequenceLock {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			actor: Default::default(),
+			input_signals: Default::default(),
+			previous: Box::from([]),
+			progress: 0,
+			inputs: Default::default(),
+			inverted_inputs: Default::default(),
+			sequence: PackedInt32Array::new(),
+			sprite: None,
+			step_sound: None,
+			fail_sound: None,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.actor = Rc::clone(&self.base().get_parent().unwrap().try_cast::<Actor>().unwrap().bind().data);
+		self.input_signals = Inputs::new(&self.inputs, &self.inverted_inputs);
+		self.previous = self.input_signals.signals().collect();
+		if self.sequence.is_empty() {
+			self.sequence = (0..self.previous.len() as i32).collect();
+		}
+		if self.sequence.is_empty() {
+			godot_warn!("{} has no sequence to enter and will never unlock", self.base().get_path());
+		}
+	}
+
+	fn physics_process(&mut self, _: f64) {
+		let current: Box<[bool]> = self.input_signals.signals().collect();
+		let rising: Vec<usize> = (0..current.len()).filter(|&i| current[i] && !self.previous[i]).collect();
+		self.previous = current;
+
+		if self.progress >= self.sequence.len() {
+			return;
+		}
+
+		for input in rising {
+			if self.sequence.get(self.progress).is_some_and(|expected| expected as usize == input) {
+				self.progress += 1;
+				self.play(self.step_sound.clone());
+				let progress = self.progress as i64;
+				self.base_mut().emit_signal("step", &[progress.to_variant()]);
+			} else {
+				self.play(self.fail_sound.clone());
+				self.base_mut().emit_signal("failed", &[]);
+				// A wrong step can still be the first step of a fresh attempt.
+				self.progress = 0;
+				if self.sequence.get(0).is_some_and(|first| first as usize == input) {
+					self.progress = 1;
+					self.base_mut().emit_signal("step", &[1i64.to_variant()]);
+				}
+			}
+
+			if self.progress == self.sequence.len() {
+				let mut data = self.actor.get();
+				data.signal = true;
+				data.level = 1.0;
+				self.actor.set(data);
+				self.base_mut().emit_signal("unlocked", &[]);
+				break;
+			}
+		}
+
+		if let Some(sprite) = self.sprite.as_mut() {
+			sprite.set_frame(self.progress as i32);
+		}
+	}
+}
+
+#[godot_api]
+impl SequenceLock {
+	#[signal]
+	fn step(progress: i64);
+	#[signal]
+	fn failed();
+	#[signal]
+	fn unlocked();
+
+	fn play(&self, sound: Option<Gd<AudioStreamPlayer2D>>) {
+		if let Some(mut sound) = sound {
+			sound.play();
+		}
+	}
+}
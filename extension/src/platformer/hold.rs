@@ -0,0 +1,138 @@
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use godot::{classes::{control::MouseFilter, ColorRect}, prelude::*};
+
+use super::{actor::{self, ActorData, Rect, SurfaceProperties}, TILEMAP_SCALE_LOG2};
+
+// What a crate and the hold manager tell each other each tick.
+#[derive(Default, Clone, Copy)]
+pub struct HoldSlot {
+	// Written by the crate: who could grab it, or who is carrying it.
+	pub offered_to: Option<InstanceId>,
+	pub held: bool,
+	// Written by the manager: this is the crate its carrier would grab.
+	pub chosen: bool,
+	// Written by the manager: the spot a held crate would be set down in is free.
+	pub can_drop: bool,
+}
+
+struct HeldCrate {
+	actor: Rc<Cell<ActorData>>,
+	slot: Rc<Cell<HoldSlot>>,
+}
+
+#[derive(Default)]
+pub struct HoldManager {
+	crates: Vec<HeldCrate>,
+	highlights: HashMap<InstanceId, Gd<ColorRect>>,
+}
+
+impl HoldManager {
+	// Also gives the crate's actor a highlight of its own to show, so the
+	// actor's and its sprites' modulate are left to the scene.
+	pub fn register(&mut self, actor: Rc<Cell<ActorData>>, slot: Rc<Cell<HoldSlot>>) {
+		let data = actor.get();
+		if let Some((id, mut node)) = data.actor.and_then(|id| Gd::<Node>::try_from_instance_id(id).ok().map(|node| (id, node))) {
+			let mut highlight = ColorRect::new_alloc();
+			highlight.set_name("HoldHighlight");
+			highlight.set_position(data.area_offset.into());
+			highlight.set_size(data.area_size.into());
+			highlight.set_mouse_filter(MouseFilter::IGNORE);
+			highlight.set_visible(false);
+			node.add_child(&highlight);
+			self.highlights.insert(id, highlight);
+		}
+		self.crates.push(HeldCrate { actor, slot });
+	}
+
+	pub fn prune_removed(&mut self) {
+		self.crates.retain(|held| !held.actor.get().removed);
+		let crates = &self.crates;
+		self.highlights.retain(|id, _| crates.iter().any(|held| held.actor.get().actor == Some(*id)));
+	}
+
+	// Chooses, for every carrier with free hands, the nearest crate offered to it.
+	// Returns the actors of the chosen crates.
+	pub fn arbitrate(&self, actors_by_id: &HashMap<InstanceId, Rc<Cell<ActorData>>>) -> Vec<InstanceId> {
+		let busy: Vec<InstanceId> = self.crates.iter()
+			.filter(|held| held.slot.get().held)
+			.filter_map(|held| held.slot.get().offered_to)
+			.collect();
+
+		let mut nearest: HashMap<InstanceId, (i64, usize)> = HashMap::new();
+		for (i, HeldCrate { actor, slot }) in self.crates.iter().enumerate() {
+			let mut state = slot.get();
+			state.chosen = false;
+			slot.set(state);
+
+			let Some(carrier) = state.offered_to.filter(|carrier| !state.held && !busy.contains(carrier)) else {
+				continue;
+			};
+			let Some(carrier_data) = actors_by_id.get(&carrier) else {
+				continue;
+			};
+			let distance = distance_squared(actor.get(), carrier_data.get());
+			if nearest.get(&carrier).is_none_or(|&(best, _)| distance < best) {
+				nearest.insert(carrier, (distance, i));
+			}
+		}
+
+		nearest.into_values().filter_map(|(_, i)| {
+			let HeldCrate { actor, slot } = &self.crates[i];
+			let mut state = slot.get();
+			state.chosen = true;
+			slot.set(state);
+			actor.get().actor
+		}).collect()
+	}
+
+	// Works out, for every held crate, whether the spot beside its carrier that
+	// letting go would put it in is clear of tiles and other solid actors.
+	pub fn check_drops(&self, actors: &[Rc<Cell<ActorData>>], actors_by_id: &HashMap<InstanceId, Rc<Cell<ActorData>>>, solid: impl Fn(Vector2i) -> bool) {
+		for HeldCrate { actor, slot } in &self.crates {
+			let mut state = slot.get();
+			let data = actor.get();
+			let Some(carrier) = data.held_by.and_then(|id| actors_by_id.get(&id)) else {
+				continue;
+			};
+			let landing = landing(data, carrier.get());
+
+			let tiles_free = (landing.tl.x >> TILEMAP_SCALE_LOG2..=(landing.br.x - 1) >> TILEMAP_SCALE_LOG2).all(|x| {
+				(landing.tl.y >> TILEMAP_SCALE_LOG2..=(landing.br.y - 1) >> TILEMAP_SCALE_LOG2).all(|y| !solid(Vector2i { x, y }))
+			});
+			let actors_free = actors.iter().all(|other| {
+				let other = other.get();
+				other.removed || other.actor == data.actor || other.actor == data.held_by ||
+					![other.top, other.left, other.bottom, other.right].iter().any(|edge| edge.contains(SurfaceProperties::SOLID)) ||
+					!other.rect().overlaps(landing)
+			});
+			state.can_drop = tiles_free && actors_free;
+			slot.set(state);
+		}
+	}
+
+	// Highlights the crates that would be grabbed, and only those.
+	pub fn show(&mut self, chosen: &[InstanceId], color: Color) {
+		for (id, highlight) in &mut self.highlights {
+			highlight.set_color(color);
+			highlight.set_visible(chosen.contains(id));
+		}
+	}
+}
+
+// A held crate moved sideways until it's clear of its carrier, on the side it's held.
+fn landing(data: ActorData, carrier: ActorData) -> Rect {
+	let (rect, carrier) = (data.rect(), carrier.rect());
+	let dx = if data.flipped { carrier.tl.x - rect.br.x } else { carrier.br.x - rect.tl.x };
+	Rect {
+		tl: rect.tl + actor::Vec { x: dx, y: 0 },
+		br: rect.br + actor::Vec { x: dx, y: 0 },
+	}
+}
+
+fn distance_squared(a: ActorData, b: ActorData) -> i64 {
+	let (a, b) = (a.rect(), b.rect());
+	let dx = (a.tl.x as i64 + a.br.x as i64 - b.tl.x as i64 - b.br.x as i64) / 2;
+	let dy = (a.tl.y as i64 + a.br.y as i64 - b.tl.y as i64 - b.br.y as i64) / 2;
+	dx * dx + dy * dy
+}
@@ -2,29 +2,47 @@ use std::{cell::{Cell, RefCell}, f32::consts::PI, rc::Rc};
 
 use godot::{prelude::*};
 
-use super::actor::{self, Actor, ActorData};
+use super::{actor::{self, Actor, ActorData, Directions, Rotator, SCENE_SCALE_INV}, signal::Inputs};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
 	Up, Down, Left, Right
 }
 
 impl Direction {
-	pub const fn reflect_main(self) -> Self {
+	pub const fn from_rotation_degrees(degrees: i32) -> Option<Self> {
+		match degrees.rem_euclid(360) {
+			0 => Some(Self::Left),
+			90 => Some(Self::Up),
+			180 => Some(Self::Right),
+			270 => Some(Self::Down),
+			_ => None,
+		}
+	}
+
+	pub const fn turn_cw(self) -> Self {
 		match self {
-			Self::Up => Self::Left,
 			Self::Left => Self::Up,
-			Self::Down => Self::Right,
+			Self::Up => Self::Right,
 			Self::Right => Self::Down,
+			Self::Down => Self::Left,
 		}
 	}
 
-	pub const fn reflect_inv(self) -> Self {
+	pub const fn turn_ccw(self) -> Self {
 		match self {
-			Self::Up => Self::Right,
-			Self::Left => Self::Down,
-			Self::Down => Self::Left,
+			Self::Up => Self::Left,
 			Self::Right => Self::Up,
+			Self::Down => Self::Right,
+			Self::Left => Self::Down,
+		}
+	}
+
+	pub const fn mirror(self) -> Self {
+		match self {
+			Self::Left => Self::Right,
+			Self::Right => Self::Left,
+			_ => self,
 		}
 	}
 
@@ -37,6 +55,29 @@ impl Direction {
 		}
 	}
 
+	pub const fn reverse(self) -> Self {
+		match self {
+			Self::Up => Self::Down,
+			Self::Left => Self::Right,
+			Self::Down => Self::Up,
+			Self::Right => Self::Left,
+		}
+	}
+
+	// The face of an actor that a beam travelling this way runs into.
+	pub const fn hit_face(self) -> Directions {
+		self.reverse().directions()
+	}
+
+	pub const fn directions(self) -> Directions {
+		match self {
+			Self::Up => Directions::UP,
+			Self::Left => Directions::LEFT,
+			Self::Down => Directions::DOWN,
+			Self::Right => Directions::RIGHT,
+		}
+	}
+
 	pub const fn tile_offset(self) -> Vector2i {
 		match self {
 			Self::Up => Vector2i::UP,
@@ -47,7 +88,110 @@ impl Direction {
 	}
 }
 
-#[derive(Clone, Copy)]
+// Which mirror sprite an orientation is drawn with, before it is turned or flipped.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MirrorSprite {
+	Flat,
+	Shallow,
+	Diagonal,
+}
+
+pub struct MirrorOrientation {
+	// Angle of the mirror line, clockwise from horizontal.
+	pub angle: i32,
+	pub sprite: MirrorSprite,
+	pub quarter_turns: i32,
+	pub flip: bool,
+	// Where a beam travelling up, down, left or right leaves the mirror; None stops it.
+	exits: [Option<Direction>; 4],
+}
+
+const fn exits(up: Option<Direction>, down: Option<Direction>, left: Option<Direction>, right: Option<Direction>) -> [Option<Direction>; 4] {
+	[up, down, left, right]
+}
+
+const UP: Option<Direction> = Some(Direction::Up);
+const DOWN: Option<Direction> = Some(Direction::Down);
+const LEFT: Option<Direction> = Some(Direction::Left);
+const RIGHT: Option<Direction> = Some(Direction::Right);
+
+// Beams only travel along the axes, so each exit is the true reflection snapped
+// to the nearest axis. A beam that would only graze the mirror is stopped.
+const MIRRORS: [MirrorOrientation; 8] = [
+	MirrorOrientation { angle: 0, sprite: MirrorSprite::Flat, quarter_turns: 0, flip: false, exits: exits(DOWN, UP, None, None) },
+	MirrorOrientation { angle: 15, sprite: MirrorSprite::Shallow, quarter_turns: 0, flip: false, exits: exits(DOWN, UP, None, None) },
+	MirrorOrientation { angle: 45, sprite: MirrorSprite::Diagonal, quarter_turns: 0, flip: false, exits: exits(LEFT, RIGHT, UP, DOWN) },
+	MirrorOrientation { angle: 75, sprite: MirrorSprite::Shallow, quarter_turns: 1, flip: true, exits: exits(None, None, RIGHT, LEFT) },
+	MirrorOrientation { angle: 90, sprite: MirrorSprite::Flat, quarter_turns: 1, flip: false, exits: exits(None, None, RIGHT, LEFT) },
+	MirrorOrientation { angle: 105, sprite: MirrorSprite::Shallow, quarter_turns: 1, flip: false, exits: exits(None, None, RIGHT, LEFT) },
+	MirrorOrientation { angle: 135, sprite: MirrorSprite::Diagonal, quarter_turns: 0, flip: true, exits: exits(RIGHT, LEFT, DOWN, UP) },
+	MirrorOrientation { angle: 165, sprite: MirrorSprite::Shallow, quarter_turns: 0, flip: true, exits: exits(DOWN, UP, None, None) },
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Mirror(usize);
+
+impl Mirror {
+	pub fn from_angle(degrees: i32) -> Option<Self> {
+		let degrees = degrees.rem_euclid(180);
+		MIRRORS.iter().position(|m| m.angle == degrees).map(Self)
+	}
+
+	pub fn orientation(self) -> &'static MirrorOrientation {
+		&MIRRORS[self.0]
+	}
+
+	// Steps through every orientation in turn, the way a held mirror is spun.
+	pub fn next(self) -> Self {
+		Self((self.0 + 1) % MIRRORS.len())
+	}
+
+	pub fn turn(self, clockwise: bool) -> Self {
+		let angle = self.orientation().angle + if clockwise { 90 } else { -90 };
+		Self::from_angle(angle).unwrap()
+	}
+
+	pub fn flip(self) -> Self {
+		Self::from_angle(180 - self.orientation().angle).unwrap()
+	}
+
+	pub fn exit(self, direction: Direction) -> Option<Direction> {
+		self.orientation().exits[direction as usize]
+	}
+
+	// How far into `rect` a beam entering at `start` travels before meeting the
+	// mirror line, which runs through the centre of the box.
+	pub fn depth(self, direction: Direction, start: actor::Vec, rect: actor::Rect) -> i32 {
+		let (sin, cos) = (self.orientation().angle as f32).to_radians().sin_cos();
+		let size = rect.br - rect.tl;
+		let centre = rect.tl + actor::Vec { x: size.x / 2, y: size.y / 2 };
+		let depth = match direction {
+			Direction::Left | Direction::Right => {
+				let x = ((start.y - centre.y) as f32 * cos / sin) as i32;
+				if direction == Direction::Right { x + size.x / 2 } else { size.x / 2 - x }
+			}
+			Direction::Up | Direction::Down => {
+				let y = ((start.x - centre.x) as f32 * sin / cos) as i32;
+				if direction == Direction::Down { y + size.y / 2 } else { size.y / 2 - y }
+			}
+		};
+		depth.clamp(0, match direction {
+			Direction::Left | Direction::Right => size.x,
+			Direction::Up | Direction::Down => size.y,
+		})
+	}
+}
+
+#[derive(GodotConvert, Var, Export, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum BeamEffect {
+	#[default]
+	None,
+	Kill,
+	Knockback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SegmentData {
 	pub start: actor::Vec,
 	pub length: i32,
@@ -60,25 +204,133 @@ pub struct Beam {
 	pub start_direction: Direction,
 	pub start_pos: actor::Vec,
 	pub hit_actor: Option<Rc<Cell<ActorData>>>,
-	pub scene: Gd<PackedScene>,
-	pub segments: Vec<Gd<Node2D>>,
-	pub top: i32,
-	pub left: i32,
-	pub bottom: i32,
-	pub right: i32,
+	pub hit_face: Directions,
+	pub segments: Vec<SegmentData>,
+	pub renderer: BeamRenderer,
+	pub bounds: actor::Rect,
+	pub mount: Option<Mount>,
+	pub dirty: bool,
+	pub effect: BeamEffect,
+}
+
+pub struct Mount {
+	pub actor: Rc<Cell<ActorData>>,
+	pub offset: actor::Vec,
+	pub direction: Direction,
+}
+
+impl Mount {
+	// Mirrors `offset` about the centre of the actor's area when it faces left.
+	pub fn place(&self) -> (actor::Vec, Direction) {
+		let data = self.actor.get();
+		if data.flipped {
+			let offset = actor::Vec {
+				x: data.area_offset.x * 2 + data.area_size.x - self.offset.x,
+				y: self.offset.y,
+			};
+			(data.pos + offset, self.direction.mirror())
+		} else {
+			(data.pos + self.offset, self.direction)
+		}
+	}
+}
+
+impl Beam {
+	pub fn follow_mount(&mut self) -> bool {
+		let Some(mount) = &self.mount else {
+			return false;
+		};
+		let (start_pos, start_direction) = mount.place();
+		let changed = start_pos != self.start_pos || start_direction != self.start_direction;
+		self.start_pos = start_pos;
+		self.start_direction = start_direction;
+		changed
+	}
+}
+
+pub struct BeamRenderer {
+	scene: Gd<PackedScene>,
+	nodes: Vec<Gd<Node2D>>,
+	shown: usize,
+}
+
+impl BeamRenderer {
+	pub fn new(scene: Gd<PackedScene>) -> Self {
+		Self {
+			scene,
+			nodes: vec![],
+			shown: 0,
+		}
+	}
+
+	// Nodes are only ever added, never freed; segments past the end are hidden
+	// so a beam flickering between lengths doesn't churn the scene tree.
+	pub fn show(&mut self, container: &mut Gd<Node>, segments: &[SegmentData]) {
+		while self.nodes.len() < segments.len() {
+			let node = self.scene.instantiate().unwrap().try_cast::<Node2D>().unwrap();
+			container.add_child(&node);
+			self.nodes.push(node);
+		}
+
+		for (i, node) in self.nodes.iter_mut().enumerate() {
+			if let Some(segment) = segments.get(i) {
+				node.set_position(segment.start.into());
+				node.set_scale(Vector2 { x: segment.length as f32 * SCENE_SCALE_INV, y: 1.0 });
+				node.set_rotation(segment.direction.rot());
+			}
+
+			let visible = i < segments.len();
+			if visible != (i < self.shown) {
+				node.set_visible(visible);
+			}
+		}
+
+		self.shown = segments.len();
+	}
 }
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
 pub struct Lazer {
 	base: Base<Node2D>,
-	input_actors: Box<[Rc<Cell<ActorData>>]>,
+	input_signals: Inputs,
 	pub beam: Option<Rc<RefCell<Beam>>>,
+	node_mount: Option<Mount>,
+	base_rotation: f32,
+	base_scale: Vector2,
+	rotator: Option<Rotator>,
+	tick: u32,
+	pulse_on: bool,
 
 	#[export]
-	inputs: Array<Gd<Actor>>,
+	inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_inputs: Array<bool>,
 	#[export]
 	beam_type: Option<Gd<PackedScene>>,
+	#[export]
+	mount: Option<Gd<Actor>>,
+	#[export]
+	rotate_inputs: Array<Gd<Node>>,
+	#[export]
+	inverted_rotate_inputs: Array<bool>,
+	#[export]
+	rotate_continuously: bool,
+	#[export]
+	rotate_period: u32,
+	#[export]
+	rotate_clockwise: bool,
+	#[export]
+	effect: BeamEffect,
+	#[export]
+	on_ticks: u32,
+	#[export]
+	off_ticks: u32,
+	#[export]
+	phase: u32,
+	// The only way a lazer without inputs is lit; otherwise no inputs means off.
+	#[export]
+	always_on: bool,
 }
 
 #[godot_api]
@@ -86,36 +338,142 @@ impl INode2D for Lazer {
 	fn init(base: Base<Node2D>) -> Self {
 		Self {
 			base,
-			input_actors: Box::from([]),
+			input_signals: Default::default(),
 			beam: None,
+			node_mount: None,
+			base_rotation: 0.0,
+			base_scale: Vector2::ONE,
+			rotator: None,
+			tick: 0,
+			pulse_on: true,
 			inputs: Default::default(),
+			inverted_inputs: Default::default(),
 			beam_type: None,
+			mount: None,
+			rotate_inputs: Default::default(),
+			inverted_rotate_inputs: Default::default(),
+			rotate_continuously: false,
+			rotate_period: 60,
+			rotate_clockwise: true,
+			effect: BeamEffect::None,
+			on_ticks: 0,
+			off_ticks: 0,
+			phase: 0,
+			always_on: false,
 		}
 	}
 
 	fn ready(&mut self) {
-		self.input_actors = self.inputs.iter_shared().map(|input| Rc::clone(&input.bind().data)).collect();
+		self.input_signals = Inputs::new(&self.inputs, &self.inverted_inputs);
+		self.rotator = Some(Rotator::new(Inputs::new(&self.rotate_inputs, &self.inverted_rotate_inputs)));
+		let degrees = self.base().get_rotation_degrees().round() as i32;
+		let start_direction = Direction::from_rotation_degrees(degrees).unwrap_or_else(|| {
+			godot_error!("{} is rotated {} degrees; lazers must face a multiple of 90", self.base().get_name(), degrees);
+			Direction::Left
+		});
+		let start_pos: actor::Vec = self.base().get_child(0).unwrap().try_cast::<Node2D>().unwrap().get_global_position().into();
+
+		// The mount's own ready may not have run yet if we're its child, so
+		// offsets are taken from its node rather than its ActorData.
+		let mut mount = None;
+		if let Some(mount_node) = self.mount.clone() {
+			let mount_pos: actor::Vec = mount_node.get_global_position().into();
+			let node_pos: actor::Vec = self.base().get_global_position().into();
+			let data = Rc::clone(&mount_node.bind().data);
+			self.base_rotation = self.base().get_global_rotation();
+			self.base_scale = self.base().get_global_scale();
+			self.node_mount = Some(Mount {
+				actor: Rc::clone(&data),
+				offset: node_pos - mount_pos,
+				direction: start_direction,
+			});
+			mount = Some(Mount {
+				actor: data,
+				offset: start_pos - mount_pos,
+				direction: start_direction,
+			});
+		}
+
 		self.beam = Some(Rc::from(RefCell::new(Beam {
-			active: self.input_actors.is_empty(),
-			start_direction: match (self.base().get_rotation_degrees() as i32).rem_euclid(360) {
-				90 => Direction::Up,
-				180 => Direction::Right,
-				270 => Direction::Down,
-				_ => Direction::Left,
-			},
-			start_pos: self.base().get_child(0).unwrap().try_cast::<Node2D>().unwrap().get_global_position().into(),
+			active: self.always_on,
+			start_direction,
+			start_pos,
 			hit_actor: None,
-			scene: self.beam_type.take().unwrap(),
+			hit_face: Directions::empty(),
 			segments: vec![],
-			top: 0,
-			left: 0,
-			bottom: 0,
-			right: 0,
+			renderer: BeamRenderer::new(self.beam_type.take().unwrap()),
+			bounds: Default::default(),
+			mount,
+			dirty: false,
+			effect: self.effect,
 		})));
 	}
 
+	fn process(&mut self, _: f64) {
+		let Some(mount) = &self.node_mount else {
+			return;
+		};
+		let (pos, _) = mount.place();
+		let (rotation, scale) = if mount.actor.get().flipped {
+			(PI - self.base_rotation, Vector2 { x: self.base_scale.x, y: -self.base_scale.y })
+		} else {
+			(self.base_rotation, self.base_scale)
+		};
+		let mut base = self.base_mut();
+		base.set_global_position(pos.into());
+		base.set_global_rotation(rotation);
+		base.set_global_scale(scale);
+	}
+
 	fn physics_process(&mut self, _: f64) {
-		let open = self.input_actors.iter().all(|i| i.get().signal);
-		self.beam.as_ref().unwrap().borrow_mut().active = open;
+		let pulse_on = self.pulse();
+		let open = pulse_on && (self.always_on || (!self.input_signals.is_empty() && self.input_signals.all()));
+		let mut beam = self.beam.as_ref().unwrap().borrow_mut();
+		beam.active = open;
+		if pulse_on != self.pulse_on {
+			self.pulse_on = pulse_on;
+			beam.dirty = true;
+		}
+		drop(beam);
+
+		if self.rotator.as_mut().unwrap().tick(self.rotate_continuously, self.rotate_period) {
+			self.rotate();
+		}
+	}
+}
+
+impl Lazer {
+	// Counts physics ticks rather than time so the cycle replays identically.
+	fn pulse(&mut self) -> bool {
+		if self.on_ticks == 0 || self.off_ticks == 0 {
+			return true;
+		}
+		let period = self.on_ticks + self.off_ticks;
+		let on = (self.tick + self.phase) % period < self.on_ticks;
+		self.tick = (self.tick + 1) % period;
+		on
+	}
+
+	fn rotate(&mut self) {
+		let clockwise = self.rotate_clockwise;
+		let turn = |direction: Direction| if clockwise { direction.turn_cw() } else { direction.turn_ccw() };
+		let angle = if clockwise { PI * 0.5 } else { PI * -0.5 };
+
+		let beam = Rc::clone(self.beam.as_ref().unwrap());
+		let mut beam = beam.borrow_mut();
+		beam.dirty = true;
+
+		if let (Some(node_mount), Some(mount)) = (self.node_mount.as_mut(), beam.mount.as_mut()) {
+			// Turn the emitter point about the lazer's origin, both relative to the mount.
+			let from_node = mount.offset - node_mount.offset;
+			mount.offset = node_mount.offset + if clockwise { from_node.rotate_cw() } else { from_node.rotate_ccw() };
+			mount.direction = turn(mount.direction);
+			node_mount.direction = mount.direction;
+			self.base_rotation += angle;
+		} else {
+			self.base_mut().rotate(angle);
+			beam.start_direction = turn(beam.start_direction);
+			beam.start_pos = self.base().get_child(0).unwrap().try_cast::<Node2D>().unwrap().get_global_position().into();
+		}
 	}
 }
\ No newline at end of file
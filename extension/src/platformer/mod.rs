@@ -1,12 +1,18 @@
-use std::{cell::{Cell, RefCell}, i32, rc::Rc};
+use std::{cell::{Cell, RefCell}, collections::HashMap, i32, rc::Rc};
 
-use actor::{Actor, ActorData, Directions, Reflection, SurfaceProperties, SCENE_SCALE_INV};
+use actor::{Actor, ActorData, Crate, Directions, SurfaceProperties};
 use godot::{classes::TileMapLayer, prelude::*};
-use lazer::{Beam, Direction, Lazer, SegmentData};
+use hold::HoldManager;
+use lazer::{Beam, BeamEffect, Lazer};
 
 mod actor;
 mod camera;
+mod hold;
 mod lazer;
+mod signal;
+mod signal_overlay;
+mod trace;
+mod validate;
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
@@ -14,12 +20,19 @@ struct PlatformerGame {
 	base: Base<Node2D>,
 	actors: Vec<Rc<Cell<ActorData>>>,
 	actors_that_move: Vec<Rc<Cell<ActorData>>>,
+	actor_snapshots: Vec<ActorData>,
+	actors_by_id: HashMap<InstanceId, Rc<Cell<ActorData>>>,
 	beams: Vec<Rc<RefCell<Beam>>>,
+	holds: HoldManager,
 
 	#[export]
 	tilemap: Option<Gd<TileMapLayer>>,
 	#[export]
 	beam_container: Option<Gd<Node>>,
+	#[export]
+	chain_push_max_speed: i32,
+	#[export]
+	hold_highlight: Color,
 }
 
 #[godot_api]
@@ -29,29 +42,54 @@ impl INode2D for PlatformerGame {
 			base,
 			actors: vec![],
 			actors_that_move: vec![],
+			actor_snapshots: vec![],
+			actors_by_id: HashMap::new(),
 			beams: vec![],
+			holds: HoldManager::default(),
 			tilemap: None,
 			beam_container: None,
+			chain_push_max_speed: 4000,
+			hold_highlight: Color::from_rgba(1.0, 1.0, 0.6, 0.35),
 		}
 	}
 
 	fn ready(&mut self) {
 		self.register_actors(self.to_gd().upcast());
+
+		let problems = validate::validate_signals(self.to_gd().upcast());
+		if validate::check_requested() {
+			let code = if problems > 0 { 1 } else { 0 };
+			self.base().get_tree().unwrap().quit_ex().exit_code(code).done();
+		}
 	}
 
 	fn physics_process(&mut self, _: f64) {
+		self.prune_removed();
+
 		let tm = self.tilemap.as_ref().unwrap();
 
 		for actor in &self.actors_that_move {
 			let mut data = actor.get();
 			data.collided_old = data.collided;
 			data.collided = Directions::empty();
+			data.standing_on = None;
 			data.fall();
 			data.next_vel = data.vel.x;
 			actor.set(data);
 		}
 
+		for actor in &self.actors_that_move {
+			let mut data = actor.get();
+			if let Some(carrier) = data.held_by.and_then(|id| self.actors_by_id.get(&id)) {
+				let carrier = carrier.get();
+				data.vel = carrier.pos + carrier.vel + data.hold_offset - data.pos;
+				data.next_vel = data.vel.x;
+				actor.set(data);
+			}
+		}
+
 		let mut dirty = true;
+		let mut rows = HashMap::new();
 
 		while dirty {
 			dirty = false;
@@ -59,7 +97,7 @@ impl INode2D for PlatformerGame {
 			for actor in &self.actors_that_move {
 				let mut data = actor.get();
 
-				if data.vel.x > 0 {
+				if data.vel.x > 0 && !data.ignores_tiles {
 					let br = data.pos + data.area_offset + data.area_size;
 					let tr = br + actor::Vec {
 						y: -data.area_size.y,
@@ -82,7 +120,7 @@ impl INode2D for PlatformerGame {
 							}
 						}
 					}
-				} else if data.vel.x < 0 {
+				} else if data.vel.x < 0 && !data.ignores_tiles {
 					let tl = data.pos + data.area_offset;
 					let bl = tl + actor::Vec {
 						y: data.area_size.y - 1,
@@ -109,17 +147,25 @@ impl INode2D for PlatformerGame {
 
 				for actor2 in &self.actors {
 					let data2 = actor2.get();
+					if data.held_by.is_some() && data.held_by == data2.actor {
+						continue;
+					}
 					let rmov = data.vel.x - data2.vel.x;
+					// Movers that ignore tiles would push an actor into them, so they
+					// stop at one already pinned against something.
+					let pinned = data2.collided.contains(if rmov > 0 { Directions::RIGHT } else { Directions::LEFT });
+					let blocking = data2.moves && (data.blocked_by_actors || (data.ignores_tiles && pinned));
 					if rmov > 0 {
 						let edge = data2.left_edge();
-						if edge.properties.any() &&
+						if (edge.properties.any() || blocking) &&
 							edge.pos.y < data.pos.y + data.area_offset.y + data.area_size.y &&
 							edge.pos.y + edge.length > data.pos.y + data.area_offset.y &&
 							edge.pos.x >= data.pos.x + data.area_offset.x + data.area_size.x &&
 							edge.pos.x < data.pos.x + data.area_offset.x + data.area_size.x + rmov
 						{
-							if edge.properties.contains(SurfaceProperties::SOLID) {
-								data.next_vel = edge.pos.x + data2.vel.x - (data.pos.x + data.area_offset.x + data.area_size.x);
+							if edge.properties.contains(SurfaceProperties::SOLID) || blocking {
+								dirty |= self.share_push(&data, actor2, &mut rows);
+								data.next_vel = edge.pos.x + actor2.get().vel.x - (data.pos.x + data.area_offset.x + data.area_size.x);
 								data.collided |= Directions::RIGHT;
 							}
 							if edge.properties.contains(SurfaceProperties::NOTIFY) {
@@ -131,14 +177,15 @@ impl INode2D for PlatformerGame {
 						}
 					} else if rmov < 0 {
 						let edge = actor2.get().right_edge();
-						if edge.properties.any() &&
+						if (edge.properties.any() || blocking) &&
 							edge.pos.y < data.pos.y + data.area_offset.y + data.area_size.y &&
 							edge.pos.y + edge.length > data.pos.y + data.area_offset.y &&
 							edge.pos.x <= data.pos.x + data.area_offset.x &&
 							edge.pos.x > data.pos.x + data.area_offset.x + rmov
 						{
-							if edge.properties.contains(SurfaceProperties::SOLID) {
-								data.next_vel = edge.pos.x + data2.vel.x - (data.pos.x + data.area_offset.x);
+							if edge.properties.contains(SurfaceProperties::SOLID) || blocking {
+								dirty |= self.share_push(&data, actor2, &mut rows);
+								data.next_vel = edge.pos.x + actor2.get().vel.x - (data.pos.x + data.area_offset.x);
 								data.collided |= Directions::LEFT;
 							}
 							if edge.properties.contains(SurfaceProperties::NOTIFY) {
@@ -154,6 +201,8 @@ impl INode2D for PlatformerGame {
 				actor.set(data);
 			}
 
+			self.carry_held(false);
+
 			for actor in &self.actors_that_move {
 				let mut data = actor.get();
 				if data.next_vel != data.vel.x {
@@ -179,7 +228,7 @@ impl INode2D for PlatformerGame {
 			for actor in &self.actors_that_move {
 				let mut data = actor.get();
 
-				if data.vel.y > 0 {
+				if data.vel.y > 0 && !data.ignores_tiles {
 					let br = data.pos + data.area_offset + data.area_size;
 					let bl = br + actor::Vec {
 						x: -data.area_size.x,
@@ -202,7 +251,7 @@ impl INode2D for PlatformerGame {
 							}
 						}
 					}
-				} else if data.vel.y < 0 {
+				} else if data.vel.y < 0 && !data.ignores_tiles {
 					let tl = data.pos + data.area_offset;
 					let tr = tl + actor::Vec {
 						x: data.area_size.x - 1,
@@ -229,18 +278,24 @@ impl INode2D for PlatformerGame {
 
 				for actor2 in &self.actors {
 					let data2 = actor2.get();
+					if data.held_by.is_some() && data.held_by == data2.actor {
+						continue;
+					}
 					let rmov = data.vel.y - data2.vel.y;
+					let pinned = data2.collided.contains(if rmov > 0 { Directions::DOWN } else { Directions::UP });
+					let blocking = data2.moves && (data.blocked_by_actors || (data.ignores_tiles && pinned));
 					if rmov > 0 {
 						let edge = data2.top_edge();
-						if edge.properties.any() &&
+						if (edge.properties.any() || blocking) &&
 							edge.pos.x < data.pos.x + data.area_offset.x + data.area_size.x &&
 							edge.pos.x + edge.length > data.pos.x + data.area_offset.x &&
 							edge.pos.y >= data.pos.y + data.area_offset.y + data.area_size.y &&
 							edge.pos.y < data.pos.y + data.area_offset.y + data.area_size.y + rmov
 						{
-							if edge.properties.contains(SurfaceProperties::SOLID) {
+							if edge.properties.contains(SurfaceProperties::SOLID) || blocking {
 								data.next_vel = edge.pos.y + data2.vel.y - (data.pos.y + data.area_offset.y + data.area_size.y);
 								data.collided |= Directions::DOWN;
+								data.standing_on = data2.actor;
 							}
 							if edge.properties.contains(SurfaceProperties::NOTIFY) {
 								Gd::<Node>::from_instance_id(data2.notify_target.unwrap()).call("collide_notify", &[
@@ -251,13 +306,13 @@ impl INode2D for PlatformerGame {
 						}
 					} else if rmov < 0 {
 						let edge = actor2.get().bottom_edge();
-						if edge.properties.any() &&
+						if (edge.properties.any() || blocking) &&
 							edge.pos.x < data.pos.x + data.area_offset.x + data.area_size.x &&
 							edge.pos.x + edge.length > data.pos.x + data.area_offset.x &&
 							edge.pos.y <= data.pos.y + data.area_offset.y &&
 							edge.pos.y > data.pos.y + data.area_offset.y + rmov
 						{
-							if edge.properties.contains(SurfaceProperties::SOLID) && (data.top.contains(SurfaceProperties::SOLID) || !data.collided.contains(Directions::UP | Directions::DOWN)) {
+							if (edge.properties.contains(SurfaceProperties::SOLID) || blocking) && (data.top.contains(SurfaceProperties::SOLID) || !data.collided.contains(Directions::UP | Directions::DOWN)) {
 								data.next_vel = edge.pos.y + data2.vel.y - (data.pos.y + data.area_offset.y);
 								data.collided |= Directions::UP;
 							}
@@ -274,6 +329,8 @@ impl INode2D for PlatformerGame {
 				actor.set(data);
 			}
 
+			self.carry_held(true);
+
 			for actor in &self.actors_that_move {
 				let mut data = actor.get();
 				if data.next_vel != data.vel.y {
@@ -290,171 +347,63 @@ impl INode2D for PlatformerGame {
 			actor.set(data);
 		}
 
+		self.update_stack_mass();
+		self.holds.check_drops(&self.actors, &self.actors_by_id, |tile| tm.get_cell_tile_data(tile).is_some_and(|t| t.get_custom_data("Solid").booleanize()));
+		// After the passes, so crates offered this tick by a collision can be
+		// grabbed in their own physics step straight after.
+		let chosen = self.holds.arbitrate(&self.actors_by_id);
+		self.holds.show(&chosen, self.hold_highlight);
+
+		let current: Vec<ActorData> = self.actors.iter().map(|actor| actor.get()).collect();
+		let changed = trace::changes(&self.actor_snapshots, &current);
+		self.actor_snapshots = current;
+
 		for beam in &self.beams {
 			let mut beam = beam.borrow_mut();
-			if beam.active == beam.segments.is_empty() || (beam.active && self.actors_that_move.iter().any(|actor| {
-				let data = actor.get();
-				(data.vel.x != 0 || data.vel.y != 0) /*&& {
-					let pmin = data.pos + actor::Vec {
-						x: data.vel.x.min(0) - data.vel.x,
-						y: data.vel.y.min(0) - data.vel.y
-					};
-					let pmax = data.pos + actor::Vec {
-						x: data.vel.x.max(0) - data.vel.x,
-						y: data.vel.y.max(0) - data.vel.y
-					};
-					let tl = pmin + data.area_offset;
-					let br = pmax + data.area_offset + data.area_size;
-					br.x >= beam.left && br.y >= beam.top && tl.x <= beam.right && tl.y <= beam.bottom
-				}*/
-			})) {
+			let dirty = std::mem::take(&mut beam.dirty);
+			let moved = beam.follow_mount() || dirty;
+			let hit = beam.hit_actor.as_ref().and_then(|hit| self.actors.iter().position(|actor| Rc::ptr_eq(actor, hit)));
+			if beam.active == beam.segments.is_empty() || (beam.active && (moved || trace::affected(beam.bounds, hit, &changed))) {
 				if let Some(actor) = beam.hit_actor.take() {
 					let mut data = actor.get();
-					data.beams -= 1;
+					if !data.beam_blind.contains(beam.hit_face) {
+						data.beams -= 1;
+					}
 					actor.set(data);
 				}
 
 				if beam.active {
-					let mut hit: Option<Rc<Cell<ActorData>>> = None;
-					let mut segment = SegmentData {
-						start: beam.start_pos,
-						direction: beam.start_direction,
-						length: i32::MAX,
-						end: false,
-					};
-					let mut segments = vec![];
-
-					while !segment.end {
-						match segment.direction {
-							Direction::Left => for actor in &self.actors {
-								let edge = actor.get().right_edge();
-								if edge.properties.opaque() && edge.pos.x < segment.start.x && edge.pos.y < segment.start.y && edge.pos.y + edge.length > segment.start.y && segment.start.x - edge.pos.x < segment.length {
-									segment.length = segment.start.x - edge.pos.x;
-									hit = Some(Rc::clone(actor));
-								}
-							}
-							Direction::Right => for actor in &self.actors {
-								let edge = actor.get().left_edge();
-								if edge.properties.opaque() && edge.pos.x > segment.start.x && edge.pos.y < segment.start.y && edge.pos.y + edge.length > segment.start.y && edge.pos.x - segment.start.x < segment.length {
-									segment.length = edge.pos.x - segment.start.x;
-									hit = Some(Rc::clone(actor));
-								}
-							}
-							Direction::Up => for actor in &self.actors {
-								let edge = actor.get().bottom_edge();
-								if edge.properties.opaque() && edge.pos.y < segment.start.y && edge.pos.x < segment.start.x && edge.pos.x + edge.length > segment.start.x && segment.start.y - edge.pos.y < segment.length {
-									segment.length = segment.start.y - edge.pos.y;
-									hit = Some(Rc::clone(actor));
-								}
-							}
-							Direction::Down => for actor in &self.actors {
-								let edge = actor.get().top_edge();
-								if edge.properties.opaque() && edge.pos.y > segment.start.y && edge.pos.x < segment.start.x && edge.pos.x + edge.length > segment.start.x && edge.pos.y - segment.start.y < segment.length {
-									segment.length = edge.pos.y - segment.start.y;
-									hit = Some(Rc::clone(actor));
-								}
-							}
-						}
-
-						let tile = self.tile_pos(segment.start);
-						let mut tile = Vector2i { x: tile.x, y: tile.y };
-						let tile_dir = segment.direction.tile_offset();
-						let mut i = 0;
-						while i <= segment.length >> 16 {
-							if self.tilemap.as_ref().unwrap().get_cell_tile_data(tile).is_some_and(|t| t.get_custom_data("Solid").booleanize()) {
-								segment.length = i << 16;
-								hit = None;
-								break;
-							}
-							i += 1;
-							tile += tile_dir;
-						}
-
-
-						segment.end = hit.as_ref().is_none_or(|a| a.get().reflection == Reflection::None) && segments.len() < 16;
-
-						if !segment.end {
-							let hit = hit.as_ref().unwrap().get();
-							let inverse = hit.reflection == Reflection::Inverse;
-							let (offset, size) = match segment.direction {
-								Direction::Up | Direction::Down => (segment.start.x - hit.pos.x - hit.area_offset.x, hit.area_size.x),
-								Direction::Left | Direction::Right => (segment.start.y - hit.pos.y - hit.area_offset.y, hit.area_size.y),
-							};
-							segment.length += match (segment.direction, inverse) {
-								(Direction::Up, true) |
-								(Direction::Down, false) |
-								(Direction::Left, true) |
-								(Direction::Right, false) => offset,
-								_ => size - offset,
-							};
-						}
+					let traced = trace::trace(beam.start_pos, beam.start_direction, &self.actor_snapshots, |tile| {
+						tm.get_cell_tile_data(tile).is_some_and(|t| t.get_custom_data("Solid").booleanize())
+					});
 
-						segments.push(segment);
-						
-						if !segment.end {
-							let hit = hit.as_ref().unwrap().get();
-							let inverse = hit.reflection == Reflection::Inverse;
-							let offset = tile_dir * segment.length;
-							segment = SegmentData {
-								start: segment.start + actor::Vec {
-									x: offset.x,
-									y: offset.y
-								},
-								direction: if inverse {
-									segment.direction.reflect_inv()
-								} else {
-									segment.direction.reflect_main()
-								},
-								length: i32::MAX,
-								end: false,
-							};
-						}
-					}
-
-					if let Some(hit) = hit {
+					if let Some(i) = traced.hit {
+						let hit = &self.actors[i];
 						let mut data = hit.get();
-						data.beams += 1;
-						hit.set(data);
-						beam.hit_actor = Some(hit);
-					}
-
-					if segments.len() < beam.segments.len() {
-						for mut segment in beam.segments.drain(segments.len()..) {
-							segment.queue_free();
-						}	
-					} else {
-						for _ in beam.segments.len()..segments.len() {
-							let segment = beam.scene.instantiate().unwrap().try_cast().unwrap();
-							self.beam_container.as_mut().unwrap().add_child(&segment);
-							beam.segments.push(segment);
-						}
-					}
-
-					let mut top = beam.start_pos.y;
-					let mut bottom = beam.start_pos.y;
-					let mut left = beam.start_pos.x;
-					let mut right = beam.start_pos.x;
-
-					for (segment, node) in segments.into_iter().zip(beam.segments.iter_mut()) {
-						node.set_position(segment.start.into());
-						node.set_scale(Vector2 { x: segment.length as f32 * SCENE_SCALE_INV, y: 1.0 });
-						node.set_rotation(segment.direction.rot());
-
-						match segment.direction {
-							Direction::Down => bottom = bottom.max(segment.start.y + segment.length),
-							Direction::Left => left = left.min(segment.start.x - segment.length),
-							Direction::Right => right = right.max(segment.start.x + segment.length),
-							Direction::Up => top = top.min(segment.start.y - segment.length),
+						if !data.beam_blind.contains(traced.hit_face) {
+							data.beams += 1;
 						}
+						hit.set(data);
+						beam.hit_actor = Some(Rc::clone(hit));
+						beam.hit_face = traced.hit_face;
 					}
 
-					beam.top = top - 1;
-					beam.bottom = bottom + 1;
-					beam.left = left - 1;
-					beam.right = right + 1;
+					beam.bounds = traced.bounds;
+					beam.renderer.show(self.beam_container.as_mut().unwrap(), &traced.segments);
+					beam.segments = traced.segments;
 				} else {
-					for mut segment in beam.segments.drain(..) {
-						segment.queue_free();
+					beam.renderer.show(self.beam_container.as_mut().unwrap(), &[]);
+					beam.segments.clear();
+				}
+			}
+
+			if beam.active && beam.effect != BeamEffect::None {
+				for (i, direction) in trace::crossed(&beam.segments, &self.actor_snapshots) {
+					if let Some(target) = self.actor_snapshots[i].notify_target {
+						Gd::<Node>::from_instance_id(target).call("beam_notify", &[
+							beam.effect.to_variant(),
+							direction.directions().bits().to_variant(),
+						]);
 					}
 				}
 			}
@@ -472,6 +421,20 @@ impl PlatformerGame {
 				if actor.get().moves {
 					self.actors_that_move.push(actor.clone());
 				}
+				self.actor_snapshots.push(actor.get());
+				if let Some(id) = actor.get().actor {
+					self.actors_by_id.insert(id, Rc::clone(&actor));
+				}
+				// Registration stops descending at an actor, so anything attached to it,
+				// like a crate or a lazer riding on a carried crate, is picked up here.
+				for child in from.get_children().iter_shared() {
+					match child.try_cast::<Crate>() {
+						Ok(cube) => self.holds.register(Rc::clone(&actor), Rc::clone(&cube.bind().hold_slot)),
+						Err(child) => if let Ok(lazer) = child.try_cast::<Lazer>() {
+							self.beams.push(Rc::clone(lazer.bind().beam.as_ref().unwrap()));
+						}
+					}
+				}
 				self.actors.push(actor);
 			}
 			Err(_) => match from.clone().try_cast::<Lazer>() {
@@ -485,6 +448,108 @@ impl PlatformerGame {
 		}
 	}
 
+	fn prune_removed(&mut self) {
+		if !self.actors.iter().any(|actor| actor.get().removed) {
+			return;
+		}
+
+		let (actors, snapshots) = self.actors.iter().zip(self.actor_snapshots.iter())
+			.filter(|(actor, _)| !actor.get().removed)
+			.map(|(actor, snapshot)| (Rc::clone(actor), *snapshot))
+			.unzip();
+		self.actors = actors;
+		self.actor_snapshots = snapshots;
+		self.actors_that_move.retain(|actor| !actor.get().removed);
+		self.actors_by_id.retain(|_, actor| !actor.get().removed);
+		self.holds.prune_removed();
+
+		for beam in &self.beams {
+			beam.borrow_mut().dirty = true;
+		}
+	}
+
+	// A pushable actor running into a free one shares its push with it, slowed
+	// by the weight of the row so far. `rows` tracks that weight for this tick;
+	// an actor already in a row isn't pushed again, so anything else running
+	// into it just stops against it and the row moves or stops together.
+	fn share_push(&self, pusher: &ActorData, pushed: &Rc<Cell<ActorData>>, rows: &mut HashMap<InstanceId, u32>) -> bool {
+		let mut data = pushed.get();
+		let (Some(pusher_id), Some(id)) = (pusher.actor, data.actor) else {
+			return false;
+		};
+		if !pusher.pushable || !data.pushable || !data.moves || pusher.held_by.is_some() || data.held_by.is_some() || rows.contains_key(&id) {
+			return false;
+		}
+
+		let weight = rows.get(&pusher_id).copied().unwrap_or(pusher.stack_mass.max(1));
+		let total = weight + data.stack_mass.max(1);
+		let speed = ((pusher.vel.x.abs() as i64 * weight as i64 / total as i64) as i32).min(self.chain_push_max_speed);
+		data.vel.x = pusher.vel.x.signum() * speed;
+		data.next_vel = data.vel.x;
+		pushed.set(data);
+		rows.insert(pusher_id, total);
+		rows.insert(id, total);
+		true
+	}
+
+	// Held actors follow their carrier's resolved movement. If one is stopped
+	// short, the carrier is held back with it rather than pushing it into a wall.
+	fn carry_held(&self, vertical: bool) {
+		let axis = |v: actor::Vec| if vertical { v.y } else { v.x };
+
+		for actor in &self.actors_that_move {
+			let mut data = actor.get();
+			let Some(carrier_cell) = data.held_by.and_then(|id| self.actors_by_id.get(&id)) else {
+				continue;
+			};
+			let mut carrier = carrier_cell.get();
+			let vel = axis(data.vel);
+			let offset = axis(data.hold_offset);
+			let follow = axis(carrier.pos) + carrier.next_vel + offset - axis(data.pos);
+
+			if vel.signum() * data.next_vel < vel.signum() * follow {
+				let limit = axis(data.pos) + data.next_vel - offset - axis(carrier.pos);
+				// The carrier feels the hit as its own, so a crate held overhead
+				// bumps ceilings and ends jumps like a taller hitbox would.
+				if carrier.next_vel > 0 && limit < carrier.next_vel {
+					carrier.next_vel = limit.max(0);
+					carrier.collided |= if vertical { Directions::DOWN } else { Directions::RIGHT };
+				} else if carrier.next_vel < 0 && limit > carrier.next_vel {
+					carrier.next_vel = limit.min(0);
+					carrier.collided |= if vertical { Directions::UP } else { Directions::LEFT };
+				}
+				carrier_cell.set(carrier);
+			} else {
+				data.next_vel = follow;
+				actor.set(data);
+			}
+		}
+	}
+
+	// Adds each actor's mass to everything beneath it, following what it stands
+	// on or is carried by. The step limit guards against support loops.
+	fn update_stack_mass(&self) {
+		for actor in &self.actors {
+			let mut data = actor.get();
+			data.stack_mass = data.mass;
+			actor.set(data);
+		}
+
+		for actor in &self.actors_that_move {
+			let data = actor.get();
+			let mut below = data.support();
+			for _ in 0..self.actors.len() {
+				let Some(support) = below.and_then(|id| self.actors_by_id.get(&id)) else {
+					break;
+				};
+				let mut support_data = support.get();
+				support_data.stack_mass += data.mass;
+				support.set(support_data);
+				below = support_data.support();
+			}
+		}
+	}
+
 	fn tile_pos(&self, v: actor::Vec) -> actor::Vec {
 		actor::Vec {
 			x: v.x >> TILEMAP_SCALE_LOG2,
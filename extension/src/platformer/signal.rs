@@ -0,0 +1,143 @@
+use std::{cell::{Cell, RefCell}, collections::HashMap, rc::Rc};
+
+use godot::prelude::*;
+
+use super::actor::{Actor, ActorData};
+
+thread_local! {
+	static FLAGS: RefCell<HashMap<String, bool>> = RefCell::new(HashMap::new());
+}
+
+pub fn flag(name: &str) -> bool {
+	FLAGS.with_borrow(|flags| flags.get(name).copied().unwrap_or(false))
+}
+
+pub fn set_flag(name: &str, on: bool) {
+	FLAGS.with_borrow_mut(|flags| flags.insert(name.to_owned(), on));
+}
+
+enum Source {
+	Actor(Rc<Cell<ActorData>>),
+	Flag(String),
+	// Anything else that can answer `is_signal_on`, e.g. a GDScript trigger or timer.
+	Node(Gd<Node>),
+	// An input that didn't resolve. It stays off whether inverted or not, so a
+	// bad path can't leave its consumer stuck on.
+	Missing,
+}
+
+pub struct Input {
+	source: Source,
+	invert: bool,
+}
+
+impl Input {
+	pub fn resolve(node: Option<Gd<Node>>, invert: bool) -> Self {
+		let Some(node) = node else {
+			return Self { source: Source::Missing, invert };
+		};
+		let source = if let Ok(actor) = node.clone().try_cast::<Actor>() {
+			Source::Actor(Rc::clone(&actor.bind().data))
+		} else if let Ok(flag) = node.clone().try_cast::<GlobalFlag>() {
+			Source::Flag(flag.bind().flag.to_string())
+		} else if node.has_method("is_signal_on") {
+			Source::Node(node)
+		} else if let Some(actor) = node.get_parent().and_then(|parent| parent.try_cast::<Actor>().ok()) {
+			Source::Actor(Rc::clone(&actor.bind().data))
+		} else {
+			godot_error!("{} is not a signal source", node.get_path());
+			Source::Missing
+		};
+
+		Self { source, invert }
+	}
+
+	pub fn signal(&self) -> bool {
+		let on = match &self.source {
+			Source::Actor(data) => data.get().signal,
+			Source::Flag(name) => flag(name),
+			Source::Node(node) => node.clone().call("is_signal_on", &[]).booleanize(),
+			Source::Missing => return false,
+		};
+		on != self.invert
+	}
+
+	pub fn level(&self) -> f32 {
+		let level = match &self.source {
+			Source::Actor(data) => data.get().level,
+			Source::Missing => return 0.0,
+			_ => if self.signal() != self.invert { 1.0 } else { 0.0 },
+		};
+		if self.invert { 1.0 - level } else { level }
+	}
+}
+
+// Typed arrays can hold nulls where a NodePath didn't resolve, which
+// `iter_shared` can't hand out, so entries are read as variants.
+pub fn array_entries(array: &Variant) -> Vec<Variant> {
+	if array.get_type() != VariantType::ARRAY {
+		return vec![];
+	}
+	let len = array.call("size", &[]).to::<i64>();
+	(0..len).map(|i| array.call("get", &[i.to_variant()])).collect()
+}
+
+#[derive(Default)]
+pub struct Inputs(Box<[Input]>);
+
+impl Inputs {
+	// Entries that didn't resolve are kept, as inputs that are always off.
+	pub fn new(nodes: &Array<Gd<Node>>, inverted: &Array<bool>) -> Self {
+		Self(array_entries(&nodes.to_variant()).into_iter().enumerate().map(|(i, entry)| {
+			Input::resolve(entry.try_to::<Gd<Node>>().ok(), inverted.get(i).unwrap_or(false))
+		}).collect())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn signals(&self) -> impl Iterator<Item = bool> + '_ {
+		self.0.iter().map(Input::signal)
+	}
+
+	pub fn all(&self) -> bool {
+		self.0.iter().all(Input::signal)
+	}
+
+	pub fn level(&self) -> f32 {
+		self.0.iter().map(Input::level).fold(1.0, f32::min)
+	}
+}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct GlobalFlag {
+	base: Base<Node>,
+
+	#[export]
+	flag: GString,
+}
+
+#[godot_api]
+impl INode for GlobalFlag {
+	fn init(base: Base<Node>) -> Self {
+		Self {
+			base,
+			flag: GString::new(),
+		}
+	}
+}
+
+#[godot_api]
+impl GlobalFlag {
+	#[func]
+	fn set_global_flag(name: GString, on: bool) {
+		set_flag(&name.to_string(), on);
+	}
+
+	#[func]
+	fn is_signal_on(&self) -> bool {
+		flag(&self.flag.to_string())
+	}
+}
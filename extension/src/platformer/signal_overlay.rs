@@ -0,0 +1,124 @@
+use godot::{classes::Engine, prelude::*};
+
+use super::{signal::{array_entries, Input}, validate::{input_nodes, owner, INPUT_PROPERTIES}};
+
+struct Wire {
+	source: Gd<Node2D>,
+	consumer: Gd<Node2D>,
+	// Resolved once when the wires are collected. None in the editor.
+	input: Option<Input>,
+}
+
+#[derive(GodotClass)]
+#[class(tool, base=Node2D)]
+pub struct SignalOverlay {
+	base: Base<Node2D>,
+	wires: Option<Vec<Wire>>,
+
+	#[export]
+	root: Option<Gd<Node>>,
+	#[export]
+	line_width: f32,
+	#[export]
+	on_color: Color,
+	#[export]
+	off_color: Color,
+	#[export]
+	editor_color: Color,
+	#[export]
+	show_in_game: bool,
+}
+
+#[godot_api]
+impl INode2D for SignalOverlay {
+	fn init(base: Base<Node2D>) -> Self {
+		Self {
+			base,
+			wires: None,
+			root: None,
+			line_width: 0.1,
+			on_color: Color::from_rgba(0.2, 1.0, 0.3, 0.8),
+			off_color: Color::from_rgba(1.0, 0.2, 0.2, 0.8),
+			editor_color: Color::from_rgba(0.4, 0.7, 1.0, 0.8),
+			show_in_game: false,
+		}
+	}
+
+	fn ready(&mut self) {
+		self.base_mut().set_z_index(100);
+		if !Engine::singleton().is_editor_hint() && !self.show_in_game {
+			self.base_mut().set_visible(false);
+		}
+	}
+
+	fn process(&mut self, _: f64) {
+		if self.base().is_visible() {
+			self.base_mut().queue_redraw();
+		}
+	}
+
+	fn draw(&mut self) {
+		// The editor's scene changes under us, so wires are only kept in game.
+		if self.wires.is_none() || Engine::singleton().is_editor_hint() {
+			let Some(root) = self.root.clone().or_else(|| self.base().get_parent()) else {
+				return;
+			};
+			let mut wires = vec![];
+			Self::collect(root, &mut wires);
+			self.wires = Some(wires);
+		}
+
+		let lines: Vec<(Vector2, Vector2, Color)> = self.wires.iter().flatten()
+			.filter(|wire| wire.source.is_instance_valid() && wire.consumer.is_instance_valid())
+			.map(|wire| (wire.source.get_global_position(), wire.consumer.get_global_position(), self.color(wire)))
+			.collect();
+		for (from, to, color) in lines {
+			let from = self.base().to_local(from);
+			let to = self.base().to_local(to);
+			let width = self.line_width;
+			self.base_mut().draw_line_ex(from, to, color).width(width).done();
+			self.base_mut().draw_circle(to, width * 1.5, color);
+		}
+	}
+}
+
+impl SignalOverlay {
+	fn collect(node: Gd<Node>, wires: &mut Vec<Wire>) {
+		if let Ok(consumer) = node.clone().try_cast::<Node2D>() {
+			for property in INPUT_PROPERTIES {
+				let Some(inputs) = input_nodes(&node, property) else {
+					continue;
+				};
+				let inverted = array_entries(&node.get(format!("inverted_{}", property).as_str()));
+				for (i, input) in inputs.into_iter().enumerate() {
+					let Some(input) = input else {
+						continue;
+					};
+					let Ok(source) = owner(&input).try_cast::<Node2D>() else {
+						continue;
+					};
+					let invert = inverted.get(i).is_some_and(Variant::booleanize);
+					wires.push(Wire {
+						source,
+						consumer: consumer.clone(),
+						input: (!Engine::singleton().is_editor_hint()).then(|| Input::resolve(Some(input), invert)),
+					});
+				}
+			}
+		}
+
+		for child in node.get_children().iter_shared() {
+			Self::collect(child, wires);
+		}
+	}
+
+	// Extension classes are only placeholders in the editor, so there's no
+	// signal state to read there.
+	fn color(&self, wire: &Wire) -> Color {
+		match &wire.input {
+			None => self.editor_color,
+			Some(input) if input.signal() => self.on_color,
+			Some(_) => self.off_color,
+		}
+	}
+}
@@ -0,0 +1,296 @@
+use godot::prelude::*;
+
+use super::{actor::{self, ActorData, Directions, Rect}, lazer::{Direction, SegmentData}, TILEMAP_SCALE_LOG2};
+
+// A beam's path, worked out from actor snapshots and a tile lookup alone so a
+// re-trace can be checked against a full recompute without a scene tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+	pub segments: Vec<SegmentData>,
+	// Index into the actors the trace was made from.
+	pub hit: Option<usize>,
+	pub hit_face: Directions,
+	pub bounds: Rect,
+}
+
+pub fn trace(start: actor::Vec, direction: Direction, actors: &[ActorData], solid: impl Fn(Vector2i) -> bool) -> Trace {
+	let mut hit: Option<usize> = None;
+	let mut segment = SegmentData {
+		start,
+		direction,
+		length: i32::MAX,
+		end: false,
+	};
+	let mut segments = vec![];
+
+	while !segment.end {
+		hit = None;
+		for (i, data) in actors.iter().enumerate() {
+			match segment.direction {
+				Direction::Left => {
+					let edge = data.right_edge();
+					if edge.properties.opaque() && edge.pos.x < segment.start.x && edge.pos.y < segment.start.y && edge.pos.y + edge.length > segment.start.y && segment.start.x - edge.pos.x < segment.length {
+						segment.length = segment.start.x - edge.pos.x;
+						hit = Some(i);
+					}
+				}
+				Direction::Right => {
+					let edge = data.left_edge();
+					if edge.properties.opaque() && edge.pos.x > segment.start.x && edge.pos.y < segment.start.y && edge.pos.y + edge.length > segment.start.y && edge.pos.x - segment.start.x < segment.length {
+						segment.length = edge.pos.x - segment.start.x;
+						hit = Some(i);
+					}
+				}
+				Direction::Up => {
+					let edge = data.bottom_edge();
+					if edge.properties.opaque() && edge.pos.y < segment.start.y && edge.pos.x < segment.start.x && edge.pos.x + edge.length > segment.start.x && segment.start.y - edge.pos.y < segment.length {
+						segment.length = segment.start.y - edge.pos.y;
+						hit = Some(i);
+					}
+				}
+				Direction::Down => {
+					let edge = data.top_edge();
+					if edge.properties.opaque() && edge.pos.y > segment.start.y && edge.pos.x < segment.start.x && edge.pos.x + edge.length > segment.start.x && edge.pos.y - segment.start.y < segment.length {
+						segment.length = edge.pos.y - segment.start.y;
+						hit = Some(i);
+					}
+				}
+			}
+		}
+
+		let mut tile = Vector2i {
+			x: segment.start.x >> TILEMAP_SCALE_LOG2,
+			y: segment.start.y >> TILEMAP_SCALE_LOG2,
+		};
+		let tile_dir = segment.direction.tile_offset();
+		let mut i = 0;
+		while i <= segment.length >> TILEMAP_SCALE_LOG2 {
+			if solid(tile) {
+				segment.length = i << TILEMAP_SCALE_LOG2;
+				hit = None;
+				break;
+			}
+			i += 1;
+			tile += tile_dir;
+		}
+
+		let face = segment.direction.hit_face();
+		let mirror = hit.and_then(|i| actors[i].mirror);
+		let exit = mirror.and_then(|m| m.exit(segment.direction));
+		let bounce = hit.is_some_and(|i| actors[i].mirror.is_none() && actors[i].beam_reflect.contains(face));
+		segment.end = (exit.is_none() && !bounce) || segments.len() >= 16;
+
+		if !segment.end && !bounce {
+			segment.length += mirror.unwrap().depth(segment.direction, segment.start, actors[hit.unwrap()].rect());
+		}
+
+		segments.push(segment);
+
+		if !segment.end {
+			let offset = tile_dir * segment.length;
+			segment = SegmentData {
+				start: segment.start + actor::Vec {
+					x: offset.x,
+					y: offset.y
+				},
+				direction: if bounce {
+					segment.direction.reverse()
+				} else {
+					exit.unwrap()
+				},
+				length: i32::MAX,
+				end: false,
+			};
+		}
+	}
+
+	let mut bounds = Rect {
+		tl: start,
+		br: start,
+	};
+
+	for segment in &segments {
+		match segment.direction {
+			Direction::Down => bounds.br.y = bounds.br.y.max(segment.start.y + segment.length),
+			Direction::Left => bounds.tl.x = bounds.tl.x.min(segment.start.x - segment.length),
+			Direction::Right => bounds.br.x = bounds.br.x.max(segment.start.x + segment.length),
+			Direction::Up => bounds.tl.y = bounds.tl.y.min(segment.start.y - segment.length),
+		}
+	}
+
+	Trace {
+		hit_face: segments.last().unwrap().direction.hit_face(),
+		segments,
+		hit,
+		bounds: Rect {
+			tl: bounds.tl + actor::Vec { x: -1, y: -1 },
+			br: bounds.br + actor::Vec { x: 1, y: 1 },
+		},
+	}
+}
+
+// Actors whose beam-relevant state differs between two snapshots, each with
+// the box it swept through.
+pub fn changes(old: &[ActorData], new: &[ActorData]) -> Vec<(usize, Rect)> {
+	old.iter().zip(new).enumerate()
+		.filter(|(_, (old, new))| new.beam_state_changed(old))
+		.map(|(i, (old, new))| (i, old.rect().union(new.rect())))
+		.collect()
+}
+
+// Whether a trace with these bounds, ending on `hit`, can be out of date: a
+// change swept across its bounds, or the actor it ends on changed.
+pub fn affected(bounds: Rect, hit: Option<usize>, changed: &[(usize, Rect)]) -> bool {
+	changed.iter().any(|&(i, swept)| swept.intersects(bounds) || hit == Some(i))
+}
+
+// Actors that feel beam effects and lie on the path, each with the direction
+// of the first segment crossing it. They need not be opaque to be hit.
+pub fn crossed(segments: &[SegmentData], actors: &[ActorData]) -> Vec<(usize, Direction)> {
+	actors.iter().enumerate()
+		.filter(|(_, data)| data.beam_effects)
+		.filter_map(|(i, data)| {
+			let rect = data.rect();
+			segments.iter().find(|segment| segment_rect(segment).intersects(rect)).map(|segment| (i, segment.direction))
+		})
+		.collect()
+}
+
+fn segment_rect(segment: &SegmentData) -> Rect {
+	let mut rect = Rect {
+		tl: segment.start,
+		br: segment.start,
+	};
+	match segment.direction {
+		Direction::Up => rect.tl.y = segment.start.y.saturating_sub(segment.length),
+		Direction::Down => rect.br.y = segment.start.y.saturating_add(segment.length),
+		Direction::Left => rect.tl.x = segment.start.x.saturating_sub(segment.length),
+		Direction::Right => rect.br.x = segment.start.x.saturating_add(segment.length),
+	}
+	rect
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::actor::SurfaceProperties;
+	use super::super::lazer::Mirror;
+
+	const TILE: i32 = 1 << TILEMAP_SCALE_LOG2;
+	const START: actor::Vec = actor::Vec { x: 0, y: TILE / 2 };
+
+	fn block(x: i32, y: i32) -> ActorData {
+		let edges = SurfaceProperties::SOLID | SurfaceProperties::OPAQUE;
+		let mut data = ActorData::default();
+		data.pos = actor::Vec { x: x * TILE, y: y * TILE };
+		data.area_size = actor::Vec { x: TILE, y: TILE };
+		data.top = edges;
+		data.left = edges;
+		data.bottom = edges;
+		data.right = edges;
+		data
+	}
+
+	fn mirror(x: i32, y: i32, angle: i32) -> ActorData {
+		let mut data = block(x, y);
+		data.mirror = Mirror::from_angle(angle);
+		data
+	}
+
+	// A 20 by 20 room walled in by tiles.
+	fn run(actors: &[ActorData]) -> Trace {
+		trace(START, Direction::Right, actors, |tile| tile.x < 0 || tile.y < 0 || tile.x >= 20 || tile.y >= 20)
+	}
+
+	// Keeps the old trace unless the change could have affected it, as the game does.
+	fn incremental(before: &[ActorData], after: &[ActorData]) -> Trace {
+		let old = run(before);
+		if affected(old.bounds, old.hit, &changes(before, after)) { run(after) } else { old }
+	}
+
+	fn check(before: &[ActorData], after: &[ActorData]) {
+		assert_eq!(incremental(before, after), run(after));
+	}
+
+	#[test]
+	fn mirror_moves() {
+		let before = [mirror(5, 0, 45), block(5, 8)];
+		let mut after = before;
+		after[0].pos.x += TILE * 3;
+		assert_ne!(run(&before), run(&after));
+		check(&before, &after);
+	}
+
+	#[test]
+	fn mirror_turns() {
+		let before = [mirror(5, 0, 45), block(5, 8)];
+		let mut after = before;
+		after[0].mirror = after[0].mirror.map(|mirror| mirror.turn(true));
+		check(&before, &after);
+	}
+
+	#[test]
+	fn door_opens() {
+		let before = [block(5, 0), block(12, 0)];
+		let mut after = before;
+		after[0].top = SurfaceProperties::empty();
+		after[0].left = SurfaceProperties::empty();
+		after[0].bottom = SurfaceProperties::empty();
+		after[0].right = SurfaceProperties::empty();
+		assert_eq!(run(&before).hit, Some(0));
+		assert_eq!(run(&after).hit, Some(1));
+		check(&before, &after);
+	}
+
+	#[test]
+	fn actor_leaves_bounds() {
+		let before = [block(5, 0), block(12, 0)];
+		let mut after = before;
+		after[0].pos.y += TILE * 4;
+		check(&before, &after);
+	}
+
+	#[test]
+	fn actor_enters_bounds() {
+		let before = [block(5, 4), block(12, 0)];
+		let mut after = before;
+		after[0].pos.y -= TILE * 4;
+		check(&before, &after);
+	}
+
+	#[test]
+	fn non_beam_state_changes() {
+		let before = [block(5, 0), block(12, 0)];
+		let mut after = before;
+		after[0].signal = true;
+		after[0].level = 1.0;
+		after[0].mass = 4;
+		after[0].vel.x = TILE;
+		assert!(changes(&before, &after).is_empty());
+		check(&before, &after);
+	}
+
+	#[test]
+	fn effects_reach_actors_that_do_not_block() {
+		let mut player = block(3, 0);
+		player.top = SurfaceProperties::empty();
+		player.left = SurfaceProperties::empty();
+		player.bottom = SurfaceProperties::empty();
+		player.right = SurfaceProperties::empty();
+		player.beam_effects = true;
+		let actors = [player, block(12, 0)];
+		let traced = run(&actors);
+		assert_eq!(traced.hit, Some(1));
+		assert_eq!(crossed(&traced.segments, &actors), vec![(0, Direction::Right)]);
+	}
+
+	#[test]
+	fn unrelated_actor_moves() {
+		let before = [block(5, 0), block(3, 10)];
+		let mut after = before;
+		after[1].pos.x += TILE;
+		let old = run(&before);
+		assert!(!affected(old.bounds, old.hit, &changes(&before, &after)));
+		check(&before, &after);
+	}
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use godot::{classes::Os, prelude::*};
+
+use super::{actor::GateKind, signal::array_entries};
+
+const SOURCE_CLASSES: &[&str] = &["Plate", "LazerDetector", "Gate", "GlobalFlag", "SequenceLock", "Lever", "TimedButton"];
+pub const INPUT_PROPERTIES: &[&str] = &["inputs", "rotate_inputs"];
+
+pub const CHECK_ARG: &str = "--check-signals";
+
+pub fn check_requested() -> bool {
+	Os::singleton().get_cmdline_user_args().as_slice().iter().any(|arg| arg.to_string() == CHECK_ARG)
+}
+
+// Typed arrays don't convert to VariantArray, so elements are read through
+// the Variant. In the editor they may still be unresolved NodePaths.
+pub fn input_nodes(node: &Gd<Node>, property: &str) -> Option<Vec<Option<Gd<Node>>>> {
+	let value = node.get(property);
+	if value.get_type() != VariantType::ARRAY {
+		return None;
+	}
+	Some(array_entries(&value).into_iter().map(|input| {
+		input.try_to::<Gd<Node>>().ok().or_else(|| {
+			input.try_to::<NodePath>().ok().and_then(|path| node.get_node_or_null(&path))
+		})
+	}).collect())
+}
+
+// Signal state lives on the Actor a source is attached to, so that's what
+// inputs point at and what the graph is keyed by. Class names are compared
+// rather than cast so this also works on editor placeholders.
+pub fn owner(node: &Gd<Node>) -> Gd<Node> {
+	if node.is_class("Actor") {
+		return node.clone();
+	}
+	match node.get_parent() {
+		Some(parent) if parent.is_class("Actor") => parent,
+		_ => node.clone(),
+	}
+}
+
+#[derive(Default)]
+struct Graph {
+	sources: HashMap<InstanceId, Gd<Node>>,
+	listened: HashSet<InstanceId>,
+	edges: HashMap<InstanceId, (Gd<Node>, Vec<InstanceId>)>,
+	problems: usize,
+}
+
+impl Graph {
+	fn warn(&mut self, message: String) {
+		godot_warn!("{}", message);
+		self.problems += 1;
+	}
+
+	fn visit(&mut self, node: Gd<Node>) {
+		if SOURCE_CLASSES.contains(&node.get_class().to_string().as_str()) {
+			let owner = owner(&node);
+			self.sources.insert(owner.instance_id(), owner);
+		}
+
+		// A delay gate's output lags its inputs, so loops through one settle
+		// rather than feeding back within a tick.
+		let delayed = node.is_class("Gate") && node.get("kind") == GateKind::Delay.to_variant() && node.get("ticks").try_to::<u32>().is_ok_and(|ticks| ticks > 0);
+
+		let mut has_inputs = false;
+		let mut is_consumer = false;
+		for property in INPUT_PROPERTIES {
+			let Some(inputs) = input_nodes(&node, property) else {
+				continue;
+			};
+			is_consumer |= *property == "inputs";
+			has_inputs |= *property == "inputs" && !inputs.is_empty();
+
+			for (i, input) in inputs.into_iter().enumerate() {
+				match input {
+					Some(input) => {
+						let input = owner(&input);
+						self.listened.insert(input.instance_id());
+						if delayed {
+							continue;
+						}
+						let consumer = owner(&node);
+						self.edges.entry(consumer.instance_id()).or_insert_with(|| (consumer, vec![])).1.push(input.instance_id());
+					}
+					None => self.warn(format!("{} has a missing or freed target at {}[{}]", node.get_path(), property, i)),
+				}
+			}
+		}
+
+		if is_consumer && !has_inputs && !node.get("always_on").booleanize() {
+			self.warn(format!("{} has no inputs", node.get_path()));
+		}
+
+		for child in node.get_children().iter_shared() {
+			self.visit(child);
+		}
+	}
+
+	fn find_cycles(&mut self) {
+		// 1: on the current path, 2: fully explored.
+		let mut state: HashMap<InstanceId, u8> = HashMap::new();
+		let mut cycles = vec![];
+		let ids: Vec<InstanceId> = self.edges.keys().copied().collect();
+		for id in ids {
+			self.cycle_from(id, &mut state, &mut vec![], &mut cycles);
+		}
+		for cycle in cycles {
+			self.warn(format!("Signal cycle: {}", cycle));
+		}
+	}
+
+	fn cycle_from(&self, id: InstanceId, state: &mut HashMap<InstanceId, u8>, path: &mut Vec<InstanceId>, cycles: &mut Vec<String>) {
+		match state.get(&id) {
+			Some(2) => return,
+			Some(1) => {
+				let start = path.iter().position(|&p| p == id).unwrap();
+				let names: Vec<String> = path[start..].iter().chain([&id]).map(|id| self.name(*id)).collect();
+				cycles.push(names.join(" <- "));
+				return;
+			}
+			_ => {}
+		}
+
+		state.insert(id, 1);
+		path.push(id);
+		if let Some((_, inputs)) = self.edges.get(&id) {
+			for &input in inputs {
+				self.cycle_from(input, state, path, cycles);
+			}
+		}
+		path.pop();
+		state.insert(id, 2);
+	}
+
+	fn name(&self, id: InstanceId) -> String {
+		self.edges.get(&id).map(|(node, _)| node)
+			.or_else(|| self.sources.get(&id))
+			.map_or_else(|| id.to_string(), |node| node.get_path().to_string())
+	}
+}
+
+pub fn validate_signals(root: Gd<Node>) -> usize {
+	let mut graph = Graph::default();
+	graph.visit(root);
+	graph.find_cycles();
+
+	let unheard: Vec<String> = graph.sources.iter()
+		.filter(|(id, _)| !graph.listened.contains(id))
+		.map(|(_, node)| node.get_path().to_string())
+		.collect();
+	for path in unheard {
+		graph.warn(format!("{} is a signal source nothing listens to", path));
+	}
+
+	graph.problems
+}